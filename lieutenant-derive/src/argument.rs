@@ -0,0 +1,101 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Error, Fields, LitStr, Result};
+
+/// Converts `ThunderStorm` to `thunder_storm`.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_uppercase() && previous_lower {
+            snake.push('_');
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+/// Reads the `#[argument(...)]` attributes, calling `f` with the key and value of each entry.
+fn parse_attributes(
+    attributes: &[Attribute],
+    mut f: impl FnMut(&str, LitStr) -> Result<()>,
+) -> Result<()> {
+    for attribute in attributes {
+        if !attribute.path().is_ident("argument") {
+            continue;
+        }
+        attribute.parse_nested_meta(|meta| {
+            let key = meta
+                .path
+                .get_ident()
+                .map(ToString::to_string)
+                .unwrap_or_default();
+            let value: LitStr = meta.value()?.parse()?;
+            f(&key, value).map_err(|err| meta.error(err))
+        })?;
+    }
+    Ok(())
+}
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "Argument can only be derived for enums",
+            ))
+        }
+    };
+
+    let mut name = snake_case(&ident.to_string());
+    parse_attributes(&input.attrs, |key, value| match key {
+        "name" => {
+            name = value.value();
+            Ok(())
+        }
+        _ => Err(Error::new(value.span(), "expected `name`")),
+    })?;
+
+    let mut variants = Vec::new();
+    let mut aliases = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                &variant.fields,
+                "Argument can only be derived for enums without fields",
+            ));
+        }
+
+        let variant_ident = &variant.ident;
+        let mut variant_name = snake_case(&variant_ident.to_string());
+        parse_attributes(&variant.attrs, |key, value| match key {
+            "rename" => {
+                variant_name = value.value();
+                Ok(())
+            }
+            "alias" => {
+                aliases.push(quote!((#value, #ident::#variant_ident)));
+                Ok(())
+            }
+            _ => Err(Error::new(value.span(), "expected `rename` or `alias`")),
+        })?;
+        variants.push(quote!((#variant_name, #ident::#variant_ident)));
+    }
+
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::lieutenant::argument::EnumArgument for #ident #type_generics #where_clause {
+            const NAME: &'static str = #name;
+            const VARIANTS: &'static [(&'static str, Self)] = &[#(#variants),*];
+            const ALIASES: &'static [(&'static str, Self)] = &[#(#aliases),*];
+        }
+
+        impl #impl_generics ::lieutenant::argument::Argument for #ident #type_generics #where_clause {
+            type Parser = ::lieutenant::argument::EnumParser<Self>;
+            type ParserState = ();
+        }
+    })
+}
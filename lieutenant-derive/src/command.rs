@@ -0,0 +1,265 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    spanned::Spanned, visit_mut::VisitMut, Error, Expr, FnArg, GenericArgument, Ident, ItemFn, Lit,
+    LitStr, Meta, Pat, PathArguments, Result, ReturnType, Type,
+};
+
+/// A part of the usage pattern.
+enum Token {
+    Literal(String),
+    Required(String),
+    Optional(String),
+}
+
+fn parse_pattern(pattern: &LitStr) -> Result<Vec<Token>> {
+    let value = pattern.value();
+    let tokens: Vec<Token> = value
+        .split_whitespace()
+        .map(|token| {
+            if let Some(name) = token.strip_prefix('<').and_then(|t| t.strip_suffix('>')) {
+                Token::Required(name.to_string())
+            } else if let Some(name) = token.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+                Token::Optional(name.to_string())
+            } else {
+                Token::Literal(token.to_string())
+            }
+        })
+        .collect();
+
+    match tokens.first() {
+        Some(Token::Literal(_)) => Ok(tokens),
+        _ => Err(Error::new(
+            pattern.span(),
+            "the pattern must start with a literal, like \"/tp <x>\"",
+        )),
+    }
+}
+
+/// Returns `T` if the type is `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Gives the elided lifetimes of the game state a name, so they can be used in the returned type.
+struct NameLifetimes {
+    lifetime: syn::Lifetime,
+    used: bool,
+}
+
+impl VisitMut for NameLifetimes {
+    fn visit_type_reference_mut(&mut self, reference: &mut syn::TypeReference) {
+        if reference.lifetime.is_none() {
+            reference.lifetime = Some(self.lifetime.clone());
+            self.used = true;
+        }
+        syn::visit_mut::visit_type_reference_mut(self, reference);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.lifetime.clone();
+            self.used = true;
+        }
+    }
+}
+
+/// A parameter of the function.
+struct Parameter<'a> {
+    name: &'a Ident,
+    ty: &'a Type,
+}
+
+pub fn expand(pattern: LitStr, function: ItemFn) -> Result<TokenStream> {
+    let tokens = parse_pattern(&pattern)?;
+    let signature = &function.sig;
+    if !signature.generics.params.is_empty() {
+        return Err(Error::new(
+            signature.generics.span(),
+            "commands can't have generic parameters",
+        ));
+    }
+    if let Some(asyncness) = &signature.asyncness {
+        return Err(Error::new(asyncness.span(), "commands can't be async"));
+    }
+
+    let parameters = signature
+        .inputs
+        .iter()
+        .map(|input| match input {
+            FnArg::Typed(typed) => match &*typed.pat {
+                Pat::Ident(pat) => Ok(Parameter {
+                    name: &pat.ident,
+                    ty: &typed.ty,
+                }),
+                pat => Err(Error::new(pat.span(), "expected a parameter name")),
+            },
+            FnArg::Receiver(receiver) => {
+                Err(Error::new(receiver.span(), "commands can't take `self`"))
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let parameter = |name: &str| {
+        parameters
+            .iter()
+            .find(|parameter| parameter.name == name)
+            .ok_or_else(|| {
+                Error::new(
+                    pattern.span(),
+                    format!("the function has no parameter `{}`", name),
+                )
+            })
+    };
+
+    // Builds the parser from the pattern, and collects the arguments in the order they are extracted.
+    let mut chain = Vec::new();
+    let mut arguments: Vec<&Ident> = Vec::new();
+    let mut extracts = Vec::new();
+    let mut optional = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let (name, is_optional) = match token {
+            Token::Literal(literal) if i == 0 => {
+                chain.push(quote!(::lieutenant::command::builder::literal(#literal)));
+                continue;
+            }
+            Token::Literal(literal) => {
+                chain.push(quote!(.space().literal(#literal)));
+                continue;
+            }
+            Token::Required(name) => (name, false),
+            Token::Optional(name) => (name, true),
+        };
+
+        let parameter = parameter(name)?;
+        if arguments.contains(&parameter.name) {
+            return Err(Error::new(
+                pattern.span(),
+                format!("`{}` is used more than once", name),
+            ));
+        }
+        let ty = parameter.ty;
+        match (is_optional, option_inner(ty)) {
+            (false, None) => {
+                chain.push(quote_spanned!(ty.span()=> .space().arg_named::<#ty>(#name)));
+                extracts.push(quote!(#ty));
+            }
+            (true, Some(inner)) => {
+                chain.push(quote_spanned!(ty.span()=> .opt_space().opt_arg_named::<#inner>(#name)));
+                extracts.push(quote!(::core::option::Option<(#inner,)>));
+                optional.push(parameter.name);
+            }
+            (false, Some(_)) => {
+                return Err(Error::new(
+                    ty.span(),
+                    format!("write `[{}]` in the pattern for an optional argument", name),
+                ))
+            }
+            (true, None) => {
+                return Err(Error::new(
+                    ty.span(),
+                    format!("the optional argument `{}` must be an `Option`", name),
+                ))
+            }
+        }
+        arguments.push(parameter.name);
+    }
+
+    // The other parameters are the game state.
+    let mut lifetimes = NameLifetimes {
+        lifetime: syn::Lifetime::new("'state", Span::call_site()),
+        used: false,
+    };
+    let (state_names, state_types): (Vec<&Ident>, Vec<Type>) = parameters
+        .iter()
+        .filter(|parameter| !arguments.contains(&parameter.name))
+        .map(|parameter| {
+            let mut ty = parameter.ty.clone();
+            lifetimes.visit_type_mut(&mut ty);
+            (parameter.name, ty)
+        })
+        .unzip();
+    let generics = if lifetimes.used {
+        let lifetime = &lifetimes.lifetime;
+        quote!(<#lifetime>)
+    } else {
+        quote!()
+    };
+
+    let call = parameters.iter().map(|parameter| {
+        let name = parameter.name;
+        if optional.contains(&name) {
+            quote!(::core::clone::Clone::clone(&#name).map(|(value,)| value))
+        } else if arguments.contains(&name) {
+            quote!(::core::clone::Clone::clone(&#name))
+        } else {
+            quote!(#name)
+        }
+    });
+
+    // The doc comments of the function become the description of the command.
+    let docs: Vec<String> = function
+        .attrs
+        .iter()
+        .filter_map(|attribute| match &attribute.meta {
+            Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Str(doc) => Some(doc.value().trim().to_string()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    let describe = if docs.is_empty() {
+        quote!()
+    } else {
+        let description = docs.join(" ");
+        quote!(.describe(#description))
+    };
+
+    let attributes = &function.attrs;
+    let visibility = &function.vis;
+    let name = &signature.ident;
+    let inputs = &signature.inputs;
+    let output = &signature.output;
+    let result = match output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+    let body = &function.block;
+
+    Ok(quote! {
+        #(#attributes)*
+        #visibility fn #name #generics() -> impl ::lieutenant::command::Command<
+            GameState = (#(#state_types,)*),
+            CommandResult = #result,
+        > {
+            #[allow(unused_imports)]
+            use ::lieutenant::command::builder::CommandBuilder as _;
+
+            #[allow(clippy::too_many_arguments)]
+            fn __command_handler(#inputs) #output #body
+
+            #(#chain)*
+                .on_call(|#(#arguments: #extracts),*| {
+                    move |#(#state_names: #state_types),*| __command_handler(#(#call),*)
+                })
+                #describe
+        }
+    })
+}
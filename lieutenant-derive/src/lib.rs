@@ -0,0 +1,39 @@
+//! Derive macros for `lieutenant`. They are re-exported by it, so depend on `lieutenant` instead of this crate.
+
+mod argument;
+mod command;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemFn, LitStr};
+
+/// Implements `Argument` and `EnumArgument` for a fieldless enum, so it can be used with `arg::<T>()`.
+///
+/// Variants are matched case-insensitively by their name in snake case. `#[argument(rename = "...")]` changes
+/// the name of a variant and `#[argument(alias = "...")]` accepts another name for it, which is not suggested.
+/// The name shown in the usage is the snake case name of the enum, which `#[argument(name = "...")]` on the
+/// enum changes.
+#[proc_macro_derive(Argument, attributes(argument))]
+pub fn derive_argument(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    argument::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Turns a function into a command, built from a usage pattern like `#[command("/tp <x> <y> <z> [reason]")]`.
+///
+/// Words of the pattern are literals, `<name>` is an argument and `[name]` an optional argument, each parsed
+/// with the type of the parameter of the same name. Optional arguments must be an `Option`. The other
+/// parameters are the game state, in the order they are written. The function then takes no parameters and
+/// returns the command, and its doc comments become the description of the command.
+///
+/// References in the game state get the lifetime `'state`, which the returned command is tied to. Prefer owned
+/// game states, like the ones a `Dispatcher` needs.
+#[proc_macro_attribute]
+pub fn command(attribute: TokenStream, item: TokenStream) -> TokenStream {
+    let pattern = parse_macro_input!(attribute as LitStr);
+    let function = parse_macro_input!(item as ItemFn);
+    command::expand(pattern, function)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -1,5 +1,6 @@
 use super::Argument;
-use crate::parser::IterParser;
+use crate::graph::{ArgumentParser, NodeKind};
+use crate::parser::{IterParser, ParseError, ParseErrorKind, Suggestion};
 
 #[derive(Default)]
 pub struct BoolParser;
@@ -13,13 +14,16 @@ impl IterParser for BoolParser {
         _state: Self::ParserState,
         input: &'p str,
     ) -> (
-        anyhow::Result<(Self::Extract, &'p str)>,
+        Result<(Self::Extract, &'p str), ParseError>,
         Option<Self::ParserState>,
     ) {
         let string = input.trim();
 
         if string.is_empty() {
-            return (Err(anyhow::anyhow!("Empty input")), None);
+            return (
+                Err(ParseError::at_token(ParseErrorKind::EmptyInput, input).in_argument(0)),
+                None,
+            );
         }
 
         let pos = string.find(' ').unwrap_or(string.len());
@@ -27,13 +31,34 @@ impl IterParser for BoolParser {
         return match &string[..pos] {
             "false" => (Ok(((false,), &string[pos..string.len()])), None),
             "true" => (Ok(((true,), &string[pos..string.len()])), None),
-            _ => (Err(anyhow::anyhow!("Invalid input (not a boolean)")), None),
+            _ => (
+                Err(ParseError::at_token(ParseErrorKind::InvalidBoolean, input).in_argument(0)),
+                None,
+            ),
         };
     }
 
     fn regex(&self) -> String {
         "(true|false)".into()
     }
+
+    fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        ["true", "false"]
+            .iter()
+            .filter_map(|value| Suggestion::complete_word(input, value))
+            .collect()
+    }
+
+    fn usage(&self) -> String {
+        String::from("<bool>")
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        vec![vec![NodeKind::argument(
+            &self.usage(),
+            ArgumentParser::Bool,
+        )]]
+    }
 }
 
 impl Argument for bool {
@@ -45,6 +70,7 @@ impl Argument for bool {
 mod tests {
     use crate::command::builder::{literal, CommandBuilder};
     use crate::command::{Command, CommandSpec};
+    use crate::parser::Suggestion;
 
     #[test]
     fn test_boolean() {
@@ -125,4 +151,19 @@ mod tests {
         let suc = command.call(("tr ue", false), "/lit tr ue");
         assert!(suc.is_err());
     }
+
+    #[test]
+    fn bool_suggest() {
+        let command: CommandSpec<(bool,), bool, _, _, _> = literal("/test")
+            .space()
+            .arg::<bool>()
+            .on_call(|arg: bool| move |_: bool| arg);
+
+        assert_eq!(
+            command.suggest("/test t", 7),
+            vec![Suggestion::new(6..7, String::from("true"))]
+        );
+        assert_eq!(command.suggest("/test ", 6).len(), 2);
+        assert!(command.suggest("/test x", 7).is_empty());
+    }
 }
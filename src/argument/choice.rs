@@ -1,4 +1,5 @@
-use crate::parser::IterParser;
+use crate::graph::NodeKind;
+use crate::parser::{IterParser, ParseError, ParseErrorKind, Suggestion};
 
 pub struct ChoiceParser {
     choices: Vec<String>,
@@ -19,7 +20,7 @@ impl IterParser for ChoiceParser {
         _: Self::ParserState,
         input: &'p str,
     ) -> (
-        anyhow::Result<(Self::Extract, &'p str)>,
+        Result<(Self::Extract, &'p str), ParseError>,
         Option<Self::ParserState>,
     ) {
         for choice in &self.choices {
@@ -29,7 +30,10 @@ impl IterParser for ChoiceParser {
                 return (Ok(((String::from(choice),), &input[choice.len()..])), None);
             }
         }
-        (Err(anyhow::anyhow!("No Choice matched")), None)
+        (
+            Err(ParseError::at_token(ParseErrorKind::UnknownChoice, input).in_argument(0)),
+            None,
+        )
     }
 
     fn regex(&self) -> String {
@@ -55,12 +59,31 @@ impl IterParser for ChoiceParser {
             .collect::<Vec<String>>()
             .join("|")
     }
+
+    fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        self.choices
+            .iter()
+            .filter_map(|choice| Suggestion::complete_word(input, choice))
+            .collect()
+    }
+
+    fn usage(&self) -> String {
+        format!("({})", self.choices.join("|"))
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        self.choices
+            .iter()
+            .map(|choice| vec![NodeKind::Literal(choice.clone())])
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::command::builder::{literal, CommandBuilder};
-    use crate::command::Command;
+    use crate::command::{Command, CommandSpec};
+    use crate::parser::Suggestion;
 
     #[test]
     fn simple() {
@@ -199,4 +222,27 @@ mod tests {
         let err = command.call(("Hello", 10), "/test fä\\r f\\r");
         assert!(err.is_err(), "{:?}", err);
     }
+
+    #[test]
+    fn suggest() {
+        let choices: Vec<String> = vec!["creative".into(), "survival".into(), "spectator".into()];
+        let command: CommandSpec<(usize,), usize, _, _, _> = literal("/gamemode")
+            .space()
+            .choice(choices)
+            .on_call(|_: String| move |_: usize| 42);
+
+        assert_eq!(
+            command.suggest("/gamemode s", 11),
+            vec![
+                Suggestion::new(10..11, String::from("survival")),
+                Suggestion::new(10..11, String::from("spectator")),
+            ]
+        );
+        assert_eq!(command.suggest("/gamemode ", 10).len(), 3);
+        assert_eq!(
+            command.suggest("/gamemode cr survival", 12),
+            vec![Suggestion::new(10..12, String::from("creative"))]
+        );
+        assert!(command.suggest("/gamemode x", 11).is_empty());
+    }
 }
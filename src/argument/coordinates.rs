@@ -0,0 +1,514 @@
+use std::{marker::PhantomData, ops::Range};
+
+use super::Argument;
+use crate::graph::{ArgumentParser, NodeKind};
+use crate::parser::{IterParser, ParseError, ParseErrorKind};
+
+/// A coordinate that is either absolute, or relative to the origin with `~`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorldCoordinate {
+    pub value: f64,
+    pub relative: bool,
+}
+
+impl WorldCoordinate {
+    pub fn resolve(&self, origin: f64) -> f64 {
+        if self.relative {
+            origin + self.value
+        } else {
+            self.value
+        }
+    }
+}
+
+/// Three coordinates, either world coordinates like `1 ~ ~-2`, or local coordinates like `^ ^1 ^`.
+/// Local coordinates are offsets to the left, up and forwards, as seen from the rotation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Coordinates {
+    World([WorldCoordinate; 3]),
+    Local([f64; 3]),
+}
+
+impl Coordinates {
+    /// Resolves the coordinates against the origin, and the rotation given as yaw and pitch in degrees.
+    /// This uses the same axes as Minecraft, so a yaw of 0 faces towards positive z.
+    pub fn resolve(&self, origin: [f64; 3], rotation: [f32; 2]) -> [f64; 3] {
+        match self {
+            Coordinates::World([x, y, z]) => [
+                x.resolve(origin[0]),
+                y.resolve(origin[1]),
+                z.resolve(origin[2]),
+            ],
+            Coordinates::Local([left, up, forwards]) => {
+                let yaw = (rotation[0] as f64 + 90.0).to_radians();
+                let pitch = -(rotation[1] as f64).to_radians();
+                // Up is the forwards direction, pitched up by another 90 degrees.
+                let up_pitch = pitch + std::f64::consts::FRAC_PI_2;
+
+                let forwards_axis = [
+                    yaw.cos() * pitch.cos(),
+                    pitch.sin(),
+                    yaw.sin() * pitch.cos(),
+                ];
+                let up_axis = [
+                    yaw.cos() * up_pitch.cos(),
+                    up_pitch.sin(),
+                    yaw.sin() * up_pitch.cos(),
+                ];
+                // The cross product of the two, negated.
+                let left_axis = [
+                    forwards_axis[2] * up_axis[1] - forwards_axis[1] * up_axis[2],
+                    forwards_axis[0] * up_axis[2] - forwards_axis[2] * up_axis[0],
+                    forwards_axis[1] * up_axis[0] - forwards_axis[0] * up_axis[1],
+                ];
+
+                let mut position = origin;
+                for (axis, value) in position.iter_mut().enumerate() {
+                    *value += forwards_axis[axis] * forwards
+                        + up_axis[axis] * up
+                        + left_axis[axis] * left;
+                }
+                position
+            }
+        }
+    }
+}
+
+/// The position of a block, where absolute coordinates have to be integers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockPos {
+    pub coordinates: Coordinates,
+}
+
+impl BlockPos {
+    /// Resolves the position, and returns the block it is in.
+    pub fn resolve(&self, origin: [f64; 3], rotation: [f32; 2]) -> [i32; 3] {
+        let [x, y, z] = self.coordinates.resolve(origin, rotation);
+        [x.floor() as i32, y.floor() as i32, z.floor() as i32]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vec3 {
+    pub coordinates: Coordinates,
+}
+
+impl Vec3 {
+    pub fn resolve(&self, origin: [f64; 3], rotation: [f32; 2]) -> [f64; 3] {
+        self.coordinates.resolve(origin, rotation)
+    }
+}
+
+/// An x and z coordinate. Local coordinates are not allowed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vec2 {
+    pub x: WorldCoordinate,
+    pub z: WorldCoordinate,
+}
+
+impl Vec2 {
+    /// Resolves the coordinates against the x and z of the origin. The rotation is not used, and only taken to
+    /// match the other coordinate arguments.
+    pub fn resolve(&self, origin: [f64; 3], _rotation: [f32; 2]) -> [f64; 2] {
+        [self.x.resolve(origin[0]), self.z.resolve(origin[2])]
+    }
+}
+
+/// A yaw and pitch in degrees. Local coordinates are not allowed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rotation {
+    pub yaw: WorldCoordinate,
+    pub pitch: WorldCoordinate,
+}
+
+impl Rotation {
+    /// Resolves the angles against the given rotation. The origin is not used, and only taken to match the
+    /// other coordinate arguments.
+    pub fn resolve(&self, _origin: [f64; 3], rotation: [f32; 2]) -> [f32; 2] {
+        [
+            self.yaw.resolve(rotation[0] as f64) as f32,
+            self.pitch.resolve(rotation[1] as f64) as f32,
+        ]
+    }
+}
+
+/// A single coordinate as it was written, before it is checked which kinds the argument allows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Coordinate {
+    Absolute(f64),
+    Relative(f64),
+    Local(f64),
+}
+
+/// The arguments made up of several coordinates separated by spaces.
+pub trait CoordinateArgument: Sized {
+    const COUNT: usize;
+    /// Whether the coordinates can be given with `^`. Then either all or none of them must be local.
+    const LOCAL: bool;
+    /// Whether absolute coordinates have to be integers.
+    const INTEGER: bool;
+    const NAME: &'static str;
+    const BRIGADIER: ArgumentParser;
+
+    /// Creates the argument from `COUNT` coordinates, that have been checked against `LOCAL`.
+    fn from_coordinates(coordinates: &[Coordinate]) -> Self;
+}
+
+fn coordinates(coordinates: &[Coordinate]) -> Coordinates {
+    match coordinates {
+        [Coordinate::Local(left), Coordinate::Local(up), Coordinate::Local(forwards)] => {
+            Coordinates::Local([*left, *up, *forwards])
+        }
+        [x, y, z] => Coordinates::World([world(x), world(y), world(z)]),
+        _ => unreachable!("Expected three coordinates"),
+    }
+}
+
+fn world(coordinate: &Coordinate) -> WorldCoordinate {
+    match *coordinate {
+        Coordinate::Absolute(value) => WorldCoordinate {
+            value,
+            relative: false,
+        },
+        Coordinate::Relative(value) => WorldCoordinate {
+            value,
+            relative: true,
+        },
+        Coordinate::Local(_) => unreachable!("Local coordinates are checked while parsing"),
+    }
+}
+
+impl CoordinateArgument for BlockPos {
+    const COUNT: usize = 3;
+    const LOCAL: bool = true;
+    const INTEGER: bool = true;
+    const NAME: &'static str = "block_pos";
+    const BRIGADIER: ArgumentParser = ArgumentParser::BlockPos;
+
+    fn from_coordinates(values: &[Coordinate]) -> Self {
+        BlockPos {
+            coordinates: coordinates(values),
+        }
+    }
+}
+
+impl CoordinateArgument for Vec3 {
+    const COUNT: usize = 3;
+    const LOCAL: bool = true;
+    const INTEGER: bool = false;
+    const NAME: &'static str = "vec3";
+    const BRIGADIER: ArgumentParser = ArgumentParser::Vec3;
+
+    fn from_coordinates(values: &[Coordinate]) -> Self {
+        Vec3 {
+            coordinates: coordinates(values),
+        }
+    }
+}
+
+impl CoordinateArgument for Vec2 {
+    const COUNT: usize = 2;
+    const LOCAL: bool = false;
+    const INTEGER: bool = false;
+    const NAME: &'static str = "vec2";
+    const BRIGADIER: ArgumentParser = ArgumentParser::Vec2;
+
+    fn from_coordinates(values: &[Coordinate]) -> Self {
+        Vec2 {
+            x: world(&values[0]),
+            z: world(&values[1]),
+        }
+    }
+}
+
+impl CoordinateArgument for Rotation {
+    const COUNT: usize = 2;
+    const LOCAL: bool = false;
+    const INTEGER: bool = false;
+    const NAME: &'static str = "rotation";
+    const BRIGADIER: ArgumentParser = ArgumentParser::Rotation;
+
+    fn from_coordinates(values: &[Coordinate]) -> Self {
+        Rotation {
+            yaw: world(&values[0]),
+            pitch: world(&values[1]),
+        }
+    }
+}
+
+/// Parses `COUNT` coordinates separated by spaces. Each of them is a number, `~` followed by an optional offset,
+/// or `^` followed by an optional offset.
+pub struct CoordinatesParser<T> {
+    coordinates: PhantomData<T>,
+}
+
+impl<T> Default for CoordinatesParser<T> {
+    fn default() -> Self {
+        Self {
+            coordinates: PhantomData,
+        }
+    }
+}
+
+pub type BlockPosParser = CoordinatesParser<BlockPos>;
+pub type Vec3Parser = CoordinatesParser<Vec3>;
+pub type Vec2Parser = CoordinatesParser<Vec2>;
+pub type RotationParser = CoordinatesParser<Rotation>;
+
+impl<T: CoordinateArgument> CoordinatesParser<T> {
+    /// Parses a single coordinate token, `range` is where it is in the input.
+    fn parse_coordinate(token: &str, range: Range<usize>) -> Result<Coordinate, ParseError> {
+        let (kind, number): (fn(f64) -> Coordinate, &str) = match token.as_bytes()[0] {
+            b'~' => (Coordinate::Relative, &token[1..]),
+            b'^' if T::LOCAL => (Coordinate::Local, &token[1..]),
+            b'^' => {
+                return Err(ParseError::new(
+                    ParseErrorKind::LocalCoordinatesNotAllowed,
+                    range,
+                ))
+            }
+            _ => (Coordinate::Absolute, token),
+        };
+
+        // An offset can be left out, but an absolute coordinate can't.
+        if number.is_empty() && token.len() > number.len() {
+            return Ok(kind(0.0));
+        }
+
+        // Only allow the characters of plain decimal numbers, so `inf`, `NaN` and exponents are rejected.
+        let integer = T::INTEGER && number.len() == token.len();
+        let valid = number
+            .bytes()
+            .all(|b| b.is_ascii_digit() || b == b'+' || b == b'-' || (b == b'.' && !integer));
+        match number.parse::<f64>() {
+            Ok(value) if valid => Ok(kind(value)),
+            _ => Err(ParseError::new(ParseErrorKind::InvalidNumber, range)),
+        }
+    }
+
+    fn parse_coordinates(input: &str) -> Result<(T, usize), ParseError> {
+        let mut coordinates = Vec::with_capacity(T::COUNT);
+        let mut end = 0;
+
+        for index in 0..T::COUNT {
+            let rest = &input[end..];
+            let token = rest.trim_start();
+            let start = end + rest.len() - token.len();
+            if (index > 0 && start == end) || token.is_empty() {
+                return Err(ParseError::new(
+                    ParseErrorKind::IncompleteCoordinates(T::COUNT),
+                    start..start,
+                ));
+            }
+
+            let len = token.find(char::is_whitespace).unwrap_or(token.len());
+            end = start + len;
+            let coordinate = Self::parse_coordinate(&token[..len], start..end)?;
+
+            // Local coordinates can't be mixed with the other kinds.
+            let local = matches!(coordinate, Coordinate::Local(_));
+            if let Some(first) = coordinates.first() {
+                if local != matches!(first, Coordinate::Local(_)) {
+                    return Err(ParseError::new(
+                        ParseErrorKind::MixedCoordinates,
+                        start..end,
+                    ));
+                }
+            }
+            coordinates.push(coordinate);
+        }
+
+        Ok((T::from_coordinates(&coordinates), end))
+    }
+}
+
+impl<T: CoordinateArgument> IterParser for CoordinatesParser<T> {
+    type Extract = (T,);
+    type ParserState = ();
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        _state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        if input.trim().is_empty() {
+            return (
+                Err(ParseError::at_token(ParseErrorKind::EmptyInput, input).in_argument(0)),
+                None,
+            );
+        }
+
+        match Self::parse_coordinates(input) {
+            Ok((coordinates, end)) => (Ok(((coordinates,), &input[end..])), None),
+            Err(err) => (Err(err.in_argument(0)), None),
+        }
+    }
+
+    fn regex(&self) -> String {
+        let number = r"[+\-]?([0-9]+(\.[0-9]*)?|\.[0-9]+)";
+        let absolute = if T::INTEGER { r"[+\-]?[0-9]+" } else { number };
+        let world = format!("(~({})?|{})", number, absolute);
+
+        let mut regex = vec![world; T::COUNT].join(r"\s+");
+        if T::LOCAL {
+            let local = format!(r"\^({})?", number);
+            regex = format!("({})|({})", regex, vec![local; T::COUNT].join(r"\s+"));
+        }
+        format!("({})", regex)
+    }
+
+    fn usage(&self) -> String {
+        format!("<{}>", T::NAME)
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        vec![vec![NodeKind::argument(&self.usage(), T::BRIGADIER)]]
+    }
+}
+
+impl Argument for BlockPos {
+    type Parser = BlockPosParser;
+    type ParserState = ();
+}
+
+impl Argument for Vec3 {
+    type Parser = Vec3Parser;
+    type ParserState = ();
+}
+
+impl Argument for Vec2 {
+    type Parser = Vec2Parser;
+    type ParserState = ();
+}
+
+impl Argument for Rotation {
+    type Parser = RotationParser;
+    type ParserState = ();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::builder::{literal, CommandBuilder};
+    use crate::command::{Command, CommandSpec};
+    use crate::regex::NFA;
+
+    fn assert_close(a: [f64; 3], b: [f64; 3]) {
+        for (a, b) in a.iter().zip(&b) {
+            assert!((a - b).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn world_coordinates() {
+        let command: CommandSpec<([f64; 3],), [f64; 3], _, _, _> = literal("/tp")
+            .space()
+            .arg::<Vec3>()
+            .on_call(|pos: Vec3| move |origin: [f64; 3]| pos.resolve(origin, [0.0, 0.0]));
+
+        assert_eq!(
+            command.call(([1.0, 2.0, 3.0],), "/tp 5 6.5 -7").unwrap(),
+            [5.0, 6.5, -7.0]
+        );
+        assert_eq!(
+            command.call(([1.0, 2.0, 3.0],), "/tp ~ ~1 ~-.5").unwrap(),
+            [1.0, 3.0, 2.5]
+        );
+        assert_eq!(
+            command.call(([1.0, 2.0, 3.0],), "/tp 0 ~ 0").unwrap(),
+            [0.0, 2.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn local_coordinates() {
+        let pos = Coordinates::Local([0.0, 0.0, 1.0]);
+
+        // Facing south, towards positive z.
+        assert_close(pos.resolve([0.0, 0.0, 0.0], [0.0, 0.0]), [0.0, 0.0, 1.0]);
+        // Facing west, towards negative x.
+        assert_close(pos.resolve([0.0, 0.0, 0.0], [90.0, 0.0]), [-1.0, 0.0, 0.0]);
+        // Looking straight up.
+        assert_close(pos.resolve([0.0, 0.0, 0.0], [0.0, -90.0]), [0.0, 1.0, 0.0]);
+
+        // When facing south, left is east.
+        let pos = Coordinates::Local([1.0, 2.0, 0.0]);
+        assert_close(pos.resolve([1.0, 1.0, 1.0], [0.0, 0.0]), [2.0, 3.0, 1.0]);
+    }
+
+    #[test]
+    fn block_pos() {
+        let command: CommandSpec<(), [i32; 3], _, _, _> = literal("/setblock")
+            .space()
+            .arg::<BlockPos>()
+            .on_call(|pos: BlockPos| move || pos.resolve([0.5, 64.0, -0.5], [0.0, 0.0]));
+
+        assert_eq!(command.call((), "/setblock ~ ~-1 ~").unwrap(), [0, 63, -1]);
+        assert_eq!(command.call((), "/setblock ^ ^ ^2").unwrap(), [0, 64, 1]);
+
+        let err = command.call((), "/setblock 1.5 2 3").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidNumber);
+        assert_eq!(err.range, 10..13);
+        assert_eq!(err.argument, Some(0));
+
+        let err = command.call((), "/setblock ^ ~ ^").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MixedCoordinates);
+        assert_eq!(err.range, 12..13);
+
+        let err = command.call((), "/setblock 1 2").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::IncompleteCoordinates(3));
+
+        assert!(command.call((), "/setblock ~~ ~ ~").is_err());
+        assert!(command.call((), "/setblock 1 2 inf").is_err());
+    }
+
+    #[test]
+    fn vec2_and_rotation() {
+        let command: CommandSpec<(), ([f64; 2], [f32; 2]), _, _, _> = literal("/spreadplayers")
+            .space()
+            .arg::<Vec2>()
+            .space()
+            .arg::<Rotation>()
+            .on_call(|center: Vec2, rotation: Rotation| {
+                move || {
+                    (
+                        center.resolve([10.0, 0.0, 20.0], [0.0, 0.0]),
+                        rotation.resolve([0.0, 0.0, 0.0], [90.0, 10.0]),
+                    )
+                }
+            });
+
+        assert_eq!(
+            command.call((), "/spreadplayers ~1 5 ~-90 0").unwrap(),
+            ([11.0, 5.0], [0.0, 0.0])
+        );
+        let err = command.call((), "/spreadplayers ^ ^ 0 0").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::LocalCoordinatesNotAllowed);
+        assert_eq!(err.range, 15..16);
+    }
+
+    #[test]
+    fn regex() {
+        let regex =
+            |parser: &dyn Fn() -> String| regex::Regex::new(&format!("^({})$", parser())).unwrap();
+        let block_pos = regex(&|| BlockPosParser::default().regex());
+        let vec3 = regex(&|| Vec3Parser::default().regex());
+        let vec2 = regex(&|| Vec2Parser::default().regex());
+
+        assert!(block_pos.is_match("~ ~1 ~-2.5"));
+        assert!(block_pos.is_match("^ ^ ^1"));
+        assert!(block_pos.is_match("1 -2 +3"));
+        assert!(!block_pos.is_match("1.5 2 3"));
+        assert!(!block_pos.is_match("^ ~ ^"));
+        assert!(!block_pos.is_match("1 2"));
+        assert!(vec3.is_match("1.5 .5 ~"));
+        assert!(vec2.is_match("~ 1"));
+        assert!(!vec2.is_match("^ ^"));
+
+        assert!(NFA::<usize>::regex(&BlockPosParser::default().regex()).is_ok());
+    }
+}
@@ -0,0 +1,700 @@
+use std::{ops::Range, str::FromStr};
+
+use super::{Argument, FloatRange, IntRange, QuotedStringParser};
+use crate::graph::{ArgumentParser, NodeKind};
+use crate::parser::{IterParser, ParseError, ParseErrorKind, Suggestion};
+
+/// The kind of entities a selector like `@a` starts from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectorTarget {
+    /// `@p`
+    NearestPlayer,
+    /// `@r`
+    RandomPlayer,
+    /// `@a`
+    AllPlayers,
+    /// `@e`
+    AllEntities,
+    /// `@s`
+    Executor,
+}
+
+impl SelectorTarget {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'p' => Some(SelectorTarget::NearestPlayer),
+            'r' => Some(SelectorTarget::RandomPlayer),
+            'a' => Some(SelectorTarget::AllPlayers),
+            'e' => Some(SelectorTarget::AllEntities),
+            's' => Some(SelectorTarget::Executor),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    Nearest,
+    Furthest,
+    Random,
+    Arbitrary,
+}
+
+/// A filter of a selector, like `distance=..5` or `type=!minecraft:cow`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    X(f64),
+    Y(f64),
+    Z(f64),
+    Dx(f64),
+    Dy(f64),
+    Dz(f64),
+    Distance(FloatRange),
+    XRotation(FloatRange),
+    YRotation(FloatRange),
+    Level(IntRange),
+    Limit(u32),
+    Sort(Sort),
+    /// The objectives and the range their score has to be in, like `scores={kills=1..}`.
+    Scores(Vec<(String, IntRange)>),
+    Type {
+        value: String,
+        negated: bool,
+    },
+    Name {
+        value: String,
+        negated: bool,
+    },
+    Tag {
+        value: String,
+        negated: bool,
+    },
+    Team {
+        value: String,
+        negated: bool,
+    },
+    Gamemode {
+        value: String,
+        negated: bool,
+    },
+    Predicate {
+        value: String,
+        negated: bool,
+    },
+}
+
+/// The filters we know. Filters that can be negated with `!` take a string.
+const FILTERS: &[&str] = &[
+    "x",
+    "y",
+    "z",
+    "dx",
+    "dy",
+    "dz",
+    "distance",
+    "x_rotation",
+    "y_rotation",
+    "level",
+    "limit",
+    "sort",
+    "scores",
+    "type",
+    "name",
+    "tag",
+    "team",
+    "gamemode",
+    "predicate",
+];
+
+/// Selects entities by a player name, a uuid, or a selector like `@e[type=minecraft:cow,limit=3]`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntitySelector {
+    Player(String),
+    Uuid(u128),
+    Selector {
+        target: SelectorTarget,
+        filters: Vec<Filter>,
+    },
+}
+
+impl EntitySelector {
+    /// Finds the selected entities using the world of the resolver.
+    pub fn resolve<R: SelectorResolver>(&self, resolver: &R) -> Vec<R::Entity> {
+        match self {
+            EntitySelector::Player(name) => resolver.player(name).into_iter().collect(),
+            EntitySelector::Uuid(uuid) => resolver.uuid(*uuid).into_iter().collect(),
+            EntitySelector::Selector { target, filters } => resolver.select(*target, filters),
+        }
+    }
+}
+
+/// Looks up the entities of a selector, this is implemented by the world of the game.
+pub trait SelectorResolver {
+    type Entity;
+
+    fn player(&self, name: &str) -> Option<Self::Entity>;
+    fn uuid(&self, uuid: u128) -> Option<Self::Entity>;
+    /// Returns the entities of the target that match all of the filters.
+    fn select(&self, target: SelectorTarget, filters: &[Filter]) -> Vec<Self::Entity>;
+}
+
+/// Reads a selector from the input, keeping track of the position for errors.
+struct Reader<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            Ok(())
+        } else {
+            let end = self.pos + self.peek().map_or(0, char::len_utf8);
+            Err(ParseError::new(
+                ParseErrorKind::ExpectedCharacter(c),
+                self.pos..end,
+            ))
+        }
+    }
+
+    /// Reads until one of the characters that end a key or value, returning the text and where it is.
+    fn read_word(&mut self) -> (&'a str, Range<usize>) {
+        let start = self.pos;
+        let rest = &self.input[start..];
+        let len = rest
+            .find(|c: char| c == ',' || c == ']' || c == '=' || c == '}' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        self.pos += len;
+        (&rest[..len], start..self.pos)
+    }
+
+    /// Reads a string value, which may be quoted.
+    fn read_string(&mut self) -> Result<(String, Range<usize>), ParseError> {
+        let start = self.pos;
+        match self.peek() {
+            Some(quote @ ('"' | '\'')) => {
+                let (string, len) = QuotedStringParser::parse_quoted(quote, &self.input[start..])
+                    .map_err(|err| err.shift(start, 0))?;
+                self.pos += len;
+                Ok((string, start..self.pos))
+            }
+            _ => {
+                let (word, range) = self.read_word();
+                Ok((word.to_string(), range))
+            }
+        }
+    }
+
+    fn read_value<T: FromStr>(&mut self, key: &str) -> Result<T, ParseError> {
+        let (word, range) = self.read_word();
+        word.parse().map_err(|_| {
+            ParseError::new(ParseErrorKind::InvalidFilterValue(key.to_string()), range)
+        })
+    }
+
+    /// Reads a number like `-1.5`. Minecraft only accepts digits, `.` and `-` here, so `inf`, `NaN`, exponents
+    /// and a leading `+` are rejected.
+    fn read_decimal(&mut self, key: &str) -> Result<f64, ParseError> {
+        let (word, range) = self.read_word();
+        let decimal = word
+            .bytes()
+            .all(|b| b.is_ascii_digit() || b"-.".contains(&b));
+        match word.parse() {
+            Ok(value) if decimal => Ok(value),
+            _ => Err(ParseError::new(
+                ParseErrorKind::InvalidFilterValue(key.to_string()),
+                range,
+            )),
+        }
+    }
+
+    fn read_filters(&mut self) -> Result<Vec<Filter>, ParseError> {
+        let mut filters = Vec::new();
+        self.expect('[')?;
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(filters);
+        }
+
+        loop {
+            self.skip_whitespace();
+            let (key, key_range) = self.read_word();
+            if !FILTERS.contains(&key) {
+                return Err(ParseError::new(
+                    ParseErrorKind::UnknownFilter(key.to_string()),
+                    key_range,
+                ));
+            }
+            self.expect('=')?;
+            self.skip_whitespace();
+
+            let negated = self.peek() == Some('!');
+            let negation = self.pos..self.pos + 1;
+            if negated {
+                self.pos += 1;
+                self.skip_whitespace();
+            }
+
+            let filter = self.read_filter(key, negated)?;
+            if negated && !Self::negatable(&filter) {
+                return Err(ParseError::new(
+                    ParseErrorKind::InvalidFilterValue(key.to_string()),
+                    negation,
+                ));
+            }
+            filters.push(filter);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                _ => break,
+            }
+        }
+
+        self.expect(']')?;
+        Ok(filters)
+    }
+
+    fn negatable(filter: &Filter) -> bool {
+        matches!(
+            filter,
+            Filter::Type { .. }
+                | Filter::Name { .. }
+                | Filter::Tag { .. }
+                | Filter::Team { .. }
+                | Filter::Gamemode { .. }
+                | Filter::Predicate { .. }
+        )
+    }
+
+    fn read_filter(&mut self, key: &str, negated: bool) -> Result<Filter, ParseError> {
+        let invalid =
+            |range| ParseError::new(ParseErrorKind::InvalidFilterValue(key.to_string()), range);
+
+        let filter = match key {
+            "x" => Filter::X(self.read_decimal(key)?),
+            "y" => Filter::Y(self.read_decimal(key)?),
+            "z" => Filter::Z(self.read_decimal(key)?),
+            "dx" => Filter::Dx(self.read_decimal(key)?),
+            "dy" => Filter::Dy(self.read_decimal(key)?),
+            "dz" => Filter::Dz(self.read_decimal(key)?),
+            "distance" => {
+                let start = self.pos;
+                let range: FloatRange = self.read_value(key)?;
+                // Distances can't be negative, so neither can either bound.
+                if range.min.is_some_and(|min| min < 0.0) || range.max.is_some_and(|max| max < 0.0)
+                {
+                    return Err(invalid(start..self.pos));
+                }
+                Filter::Distance(range)
+            }
+            "x_rotation" => Filter::XRotation(self.read_value(key)?),
+            "y_rotation" => Filter::YRotation(self.read_value(key)?),
+            "level" => Filter::Level(self.read_value(key)?),
+            "limit" => {
+                let start = self.pos;
+                match self.read_value(key)? {
+                    0 => return Err(invalid(start..self.pos)),
+                    limit => Filter::Limit(limit),
+                }
+            }
+            "sort" => {
+                let (word, range) = self.read_word();
+                Filter::Sort(match word {
+                    "nearest" => Sort::Nearest,
+                    "furthest" => Sort::Furthest,
+                    "random" => Sort::Random,
+                    "arbitrary" => Sort::Arbitrary,
+                    _ => return Err(invalid(range)),
+                })
+            }
+            "scores" => {
+                let mut scores = Vec::new();
+                self.expect('{')?;
+                self.skip_whitespace();
+                while self.peek() != Some('}') {
+                    let (objective, range) = self.read_word();
+                    if objective.is_empty() {
+                        return Err(invalid(range));
+                    }
+                    self.expect('=')?;
+                    self.skip_whitespace();
+                    scores.push((objective.to_string(), self.read_value(key)?));
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(',') => {
+                            self.pos += 1;
+                            self.skip_whitespace();
+                        }
+                        _ => break,
+                    }
+                }
+                self.expect('}')?;
+                Filter::Scores(scores)
+            }
+            _ => {
+                let (value, range) = self.read_string()?;
+                // Only tags and teams can be empty, to select entities without any.
+                if value.is_empty() && key != "tag" && key != "team" {
+                    return Err(invalid(range));
+                }
+                match key {
+                    "type" => Filter::Type { value, negated },
+                    "name" => Filter::Name { value, negated },
+                    "tag" => Filter::Tag { value, negated },
+                    "team" => Filter::Team { value, negated },
+                    "gamemode" => Filter::Gamemode { value, negated },
+                    _ => Filter::Predicate { value, negated },
+                }
+            }
+        };
+        Ok(filter)
+    }
+}
+
+/// Parses a uuid in the usual hyphenated form, like `f81d4fae-7dec-11d0-a765-00a0c91e6bf6`.
+/// Like Minecraft, the groups may be shorter than usual.
+fn parse_uuid(string: &str) -> Option<u128> {
+    let groups: Vec<&str> = string.split('-').collect();
+    let widths = [8, 4, 4, 4, 12];
+    if groups.len() != widths.len() {
+        return None;
+    }
+
+    let mut uuid = 0u128;
+    for (group, width) in groups.iter().zip(widths) {
+        if group.is_empty() || group.len() > width || !group.bytes().all(|b| b.is_ascii_hexdigit())
+        {
+            return None;
+        }
+        uuid = (uuid << (width * 4)) | u128::from_str_radix(group, 16).ok()?;
+    }
+    Some(uuid)
+}
+
+fn valid_player_name(name: &str) -> bool {
+    (1..=16).contains(&name.len()) && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+#[derive(Default)]
+pub struct EntitySelectorParser {}
+
+impl EntitySelectorParser {
+    fn parse_selector(input: &str) -> Result<(EntitySelector, usize), ParseError> {
+        let mut reader = Reader { input, pos: 0 };
+
+        if reader.peek() != Some('@') {
+            let (word, range) = reader.read_word();
+            if let Some(uuid) = parse_uuid(word) {
+                return Ok((EntitySelector::Uuid(uuid), range.end));
+            }
+            if valid_player_name(word) {
+                return Ok((EntitySelector::Player(word.to_string()), range.end));
+            }
+            return Err(ParseError::at_token(ParseErrorKind::InvalidEntity, input));
+        }
+
+        let target = input[1..]
+            .chars()
+            .next()
+            .and_then(SelectorTarget::from_char);
+        let target = match target {
+            Some(target) => target,
+            None => return Err(ParseError::at_token(ParseErrorKind::UnknownSelector, input)),
+        };
+        reader.pos = 2;
+
+        let filters = match reader.peek() {
+            Some('[') => reader.read_filters()?,
+            _ => Vec::new(),
+        };
+
+        match reader.peek() {
+            Some(c) if !c.is_whitespace() => {
+                Err(ParseError::at_token(ParseErrorKind::UnknownSelector, input))
+            }
+            _ => Ok((EntitySelector::Selector { target, filters }, reader.pos)),
+        }
+    }
+}
+
+impl IterParser for EntitySelectorParser {
+    type Extract = (EntitySelector,);
+    type ParserState = ();
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        _state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        match input.chars().next() {
+            None => {
+                return (
+                    Err(ParseError::at_token(ParseErrorKind::EmptyInput, input).in_argument(0)),
+                    None,
+                )
+            }
+            Some(c) if c.is_whitespace() => {
+                return (
+                    Err(ParseError::at_token(ParseErrorKind::EmptyInput, input).in_argument(0)),
+                    None,
+                )
+            }
+            Some(_) => {}
+        }
+
+        match Self::parse_selector(input) {
+            Ok((selector, end)) => (Ok(((selector,), &input[end..])), None),
+            Err(err) => (Err(err.in_argument(0)), None),
+        }
+    }
+
+    fn regex(&self) -> String {
+        let filters = r#"\[([^\]"']|"([^"\\]|\\.)*"|'([^'\\]|\\.)*')*\]"#;
+        let uuid = r"[0-9a-fA-F]{1,8}-[0-9a-fA-F]{1,4}-[0-9a-fA-F]{1,4}-[0-9a-fA-F]{1,4}-[0-9a-fA-F]{1,12}";
+        format!("(@[prase]({})?|[a-zA-Z0-9_]{{1,16}}|{})", filters, uuid)
+    }
+
+    fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        ["@p", "@r", "@a", "@e", "@s"]
+            .iter()
+            .filter_map(|selector| Suggestion::complete_word(input, selector))
+            .collect()
+    }
+
+    fn usage(&self) -> String {
+        String::from("<entity>")
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        vec![vec![NodeKind::argument(
+            &self.usage(),
+            ArgumentParser::Entity {
+                single: false,
+                players_only: false,
+            },
+        )]]
+    }
+}
+
+impl Argument for EntitySelector {
+    type Parser = EntitySelectorParser;
+    type ParserState = ();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::builder::{literal, CommandBuilder};
+    use crate::command::{Command, CommandSpec};
+    use crate::regex::NFA;
+
+    fn select(input: &str) -> Result<EntitySelector, ParseError> {
+        let command: CommandSpec<(), EntitySelector, _, _, _> = literal("/kill")
+            .space()
+            .arg::<EntitySelector>()
+            .on_call(|selector: EntitySelector| move || selector.clone());
+        command.call((), input)
+    }
+
+    #[test]
+    fn names_and_uuids() {
+        assert_eq!(
+            select("/kill Notch").unwrap(),
+            EntitySelector::Player(String::from("Notch"))
+        );
+        assert_eq!(
+            select("/kill f81d4fae-7dec-11d0-a765-00a0c91e6bf6").unwrap(),
+            EntitySelector::Uuid(0xf81d4fae_7dec_11d0_a765_00a0c91e6bf6)
+        );
+        assert_eq!(select("/kill 0-0-0-0-1").unwrap(), EntitySelector::Uuid(1));
+
+        let err = select("/kill this_name_is_too_long").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidEntity);
+        assert_eq!(err.range, 6..27);
+    }
+
+    #[test]
+    fn selectors() {
+        assert_eq!(
+            select("/kill @s").unwrap(),
+            EntitySelector::Selector {
+                target: SelectorTarget::Executor,
+                filters: vec![]
+            }
+        );
+        assert_eq!(
+            select("/kill @a[distance=..5,type=!minecraft:cow,limit=3,sort=nearest]").unwrap(),
+            EntitySelector::Selector {
+                target: SelectorTarget::AllPlayers,
+                filters: vec![
+                    Filter::Distance(FloatRange {
+                        min: None,
+                        max: Some(5.0)
+                    }),
+                    Filter::Type {
+                        value: String::from("minecraft:cow"),
+                        negated: true
+                    },
+                    Filter::Limit(3),
+                    Filter::Sort(Sort::Nearest),
+                ]
+            }
+        );
+        assert_eq!(
+            select("/kill @e[ name = \"Big Bob\" , scores={kills=1..,deaths=0}, level=3, tag=]")
+                .unwrap(),
+            EntitySelector::Selector {
+                target: SelectorTarget::AllEntities,
+                filters: vec![
+                    Filter::Name {
+                        value: String::from("Big Bob"),
+                        negated: false
+                    },
+                    Filter::Scores(vec![
+                        (
+                            String::from("kills"),
+                            IntRange {
+                                min: Some(1),
+                                max: None
+                            }
+                        ),
+                        (
+                            String::from("deaths"),
+                            IntRange {
+                                min: Some(0),
+                                max: Some(0)
+                            }
+                        ),
+                    ]),
+                    Filter::Level(IntRange {
+                        min: Some(3),
+                        max: Some(3)
+                    }),
+                    Filter::Tag {
+                        value: String::new(),
+                        negated: false
+                    },
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let err = select("/kill @a[distanse=..5]").unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::UnknownFilter(String::from("distanse"))
+        );
+        assert_eq!(err.range, 9..17);
+        assert_eq!(err.argument, Some(0));
+
+        let err = select("/kill @a[limit=0]").unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::InvalidFilterValue(String::from("limit"))
+        );
+        assert_eq!(err.range, 15..16);
+
+        let err = select("/kill @a[limit=!3]").unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::InvalidFilterValue(String::from("limit"))
+        );
+        assert_eq!(err.range, 15..16);
+
+        let err = select("/kill @a[distance=5..1]").unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::InvalidFilterValue(String::from("distance"))
+        );
+
+        let err = select("/kill @a[distance=..-1]").unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::InvalidFilterValue(String::from("distance"))
+        );
+        assert_eq!(err.range, 18..22);
+
+        for value in &["inf", "NaN", "1e5", "-infinity", "+2"] {
+            let err = select(&format!("/kill @a[x={}]", value)).unwrap_err();
+            assert_eq!(
+                err.kind,
+                ParseErrorKind::InvalidFilterValue(String::from("x"))
+            );
+            assert_eq!(err.range, 11..11 + value.len());
+        }
+        assert!(select("/kill @a[x=-1.5,dz=2.]").is_ok());
+
+        let err = select("/kill @a[limit=3").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::ExpectedCharacter(']'));
+        assert_eq!(err.range, 16..16);
+
+        let err = select("/kill @x").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnknownSelector);
+        assert_eq!(err.range, 6..8);
+    }
+
+    #[test]
+    fn resolve() {
+        struct World;
+
+        impl SelectorResolver for World {
+            type Entity = &'static str;
+
+            fn player(&self, name: &str) -> Option<&'static str> {
+                (name == "Notch").then_some("Notch")
+            }
+
+            fn uuid(&self, _uuid: u128) -> Option<&'static str> {
+                None
+            }
+
+            fn select(&self, target: SelectorTarget, filters: &[Filter]) -> Vec<&'static str> {
+                match (target, filters) {
+                    (SelectorTarget::AllPlayers, []) => vec!["Notch", "jeb_"],
+                    _ => vec![],
+                }
+            }
+        }
+
+        assert_eq!(
+            select("/kill Notch").unwrap().resolve(&World),
+            vec!["Notch"]
+        );
+        assert_eq!(
+            select("/kill @a").unwrap().resolve(&World),
+            vec!["Notch", "jeb_"]
+        );
+    }
+
+    #[test]
+    fn regex() {
+        let parser = EntitySelectorParser::default();
+        let regex = regex::Regex::new(&format!("^({})$", parser.regex())).unwrap();
+
+        assert!(regex.is_match("@a"));
+        assert!(regex.is_match(r#"@e[name="a]b",limit=1]"#));
+        assert!(regex.is_match("Notch"));
+        assert!(regex.is_match("f81d4fae-7dec-11d0-a765-00a0c91e6bf6"));
+        assert!(!regex.is_match("@x"));
+        assert!(NFA::<usize>::regex(&parser.regex()).is_ok());
+    }
+}
@@ -0,0 +1,190 @@
+use std::marker::PhantomData;
+
+use crate::graph::NodeKind;
+use crate::parser::{IterParser, ParseError, ParseErrorKind, Suggestion};
+
+/// A fieldless enum parsed from one of a fixed set of words, matched case-insensitively. It is usually
+/// implemented with `#[derive(Argument)]`, which also implements [`Argument`](super::Argument) with an
+/// [`EnumParser`]:
+///
+/// ```
+/// use lieutenant::argument::Argument;
+///
+/// #[derive(Argument, Clone, Copy, Debug, PartialEq)]
+/// enum GameMode {
+///     Survival,
+///     Creative,
+///     #[argument(alias = "sp")]
+///     Spectator,
+///     #[argument(rename = "hardcore")]
+///     Hard,
+/// }
+/// ```
+pub trait EnumArgument: Clone + 'static {
+    /// The name used in the usage, like `game_mode`.
+    const NAME: &'static str;
+    /// The name of every variant. These are suggested and exported as literals.
+    const VARIANTS: &'static [(&'static str, Self)];
+    /// Other names that are accepted for a variant, but not suggested.
+    const ALIASES: &'static [(&'static str, Self)];
+}
+
+pub struct EnumParser<T> {
+    choices: PhantomData<T>,
+}
+
+impl<T> Default for EnumParser<T> {
+    fn default() -> Self {
+        Self {
+            choices: PhantomData,
+        }
+    }
+}
+
+impl<T: EnumArgument> IterParser for EnumParser<T> {
+    type Extract = (T,);
+    type ParserState = ();
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        _state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        let end = input.find(char::is_whitespace).unwrap_or(input.len());
+        if end == 0 {
+            return (
+                Err(ParseError::at_token(ParseErrorKind::EmptyInput, input).in_argument(0)),
+                None,
+            );
+        }
+
+        let word = &input[..end];
+        match T::VARIANTS
+            .iter()
+            .chain(T::ALIASES)
+            .find(|(name, _)| name.eq_ignore_ascii_case(word))
+        {
+            Some((_, value)) => (Ok(((value.clone(),), &input[end..])), None),
+            None => (
+                Err(ParseError::new(ParseErrorKind::UnknownChoice, 0..end).in_argument(0)),
+                None,
+            ),
+        }
+    }
+
+    fn regex(&self) -> String {
+        let names: Vec<String> = T::VARIANTS
+            .iter()
+            .chain(T::ALIASES)
+            .map(|(name, _)| regex::escape(name))
+            .collect();
+        format!("(?i:{})", names.join("|"))
+    }
+
+    fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        T::VARIANTS
+            .iter()
+            .filter_map(|(name, _)| Suggestion::complete_word(input, name))
+            .collect()
+    }
+
+    fn usage(&self) -> String {
+        format!("<{}>", T::NAME)
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        T::VARIANTS
+            .iter()
+            .chain(T::ALIASES)
+            .map(|(name, _)| vec![NodeKind::Literal(name.to_string())])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::argument::Argument;
+    use crate::command::builder::{literal, CommandBuilder};
+    use crate::command::{Command, CommandSpec};
+    use crate::graph::CommandGraph;
+    use crate::regex::NFA;
+
+    #[derive(Argument, Clone, Copy, Debug, PartialEq)]
+    enum GameMode {
+        Survival,
+        Creative,
+        #[argument(alias = "sp", alias = "3")]
+        Spectator,
+    }
+
+    #[derive(Argument, Clone, Debug, PartialEq)]
+    #[argument(name = "weather")]
+    enum WeatherKind {
+        Clear,
+        #[argument(rename = "rain")]
+        Rainy,
+        ThunderStorm,
+    }
+
+    #[test]
+    fn derive() {
+        assert_eq!(GameMode::NAME, "game_mode");
+        assert_eq!(WeatherKind::NAME, "weather");
+        assert_eq!(
+            WeatherKind::VARIANTS
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>(),
+            vec!["clear", "rain", "thunder_storm"]
+        );
+    }
+
+    #[test]
+    fn argument() {
+        let command: CommandSpec<(), GameMode, _, _, _> = literal("/gamemode")
+            .space()
+            .arg::<GameMode>()
+            .on_call(|mode: GameMode| move || mode);
+
+        assert_eq!(
+            command.call((), "/gamemode creative").unwrap(),
+            GameMode::Creative
+        );
+        assert_eq!(
+            command.call((), "/gamemode SURVIVAL").unwrap(),
+            GameMode::Survival
+        );
+        assert_eq!(
+            command.call((), "/gamemode sp").unwrap(),
+            GameMode::Spectator
+        );
+        assert_eq!(
+            command.call((), "/gamemode 3").unwrap(),
+            GameMode::Spectator
+        );
+
+        let err = command.call((), "/gamemode adventure").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnknownChoice);
+        assert_eq!(err.range, 10..19);
+
+        assert_eq!(
+            command.suggest("/gamemode s", 11),
+            vec![
+                Suggestion::new(10..11, String::from("survival")),
+                Suggestion::new(10..11, String::from("spectator"))
+            ]
+        );
+        assert_eq!(command.usage(), "/gamemode <game_mode>");
+        assert!(NFA::<usize>::regex(&EnumParser::<GameMode>::default().regex()).is_ok());
+
+        let graph = CommandGraph::from_command(&command);
+        let gamemode = graph.child(0, "gamemode").unwrap();
+        assert!(graph.child(gamemode, "creative").is_some());
+        assert!(graph.child(gamemode, "sp").is_some());
+    }
+}
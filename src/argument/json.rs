@@ -0,0 +1,544 @@
+use std::{fmt, ops::Range, str::FromStr};
+
+use indexmap::IndexMap;
+
+use super::Argument;
+use crate::graph::{ArgumentParser, NodeKind};
+use crate::parser::{IterParser, ParseError, ParseErrorKind};
+
+/// How deep arrays and objects can be nested.
+pub const MAX_JSON_DEPTH: usize = 256;
+
+/// A JSON value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// The entries are kept in the order they were written. A repeated key overwrites the earlier value.
+    Object(IndexMap<String, Json>),
+}
+
+impl Json {
+    /// Gets the value of a key, if this is an object containing it.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Parses one JSON value at the start of the input, returning it and the length it had.
+    pub(crate) fn parse_prefix(input: &str) -> Result<(Json, usize), ParseError> {
+        let mut reader = Reader {
+            input,
+            pos: 0,
+            depth: 0,
+        };
+        let value = reader.value()?;
+        Ok((value, reader.pos))
+    }
+}
+
+/// A cursor over the input that reads one value at a time.
+struct Reader<'a> {
+    input: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .find(|c| !matches!(c, ' ' | '\t' | '\n' | '\r'))
+            .unwrap_or(rest.len());
+        self.pos += len;
+    }
+
+    /// The range of the next character, or an empty range at the end of the input.
+    fn next_range(&self) -> Range<usize> {
+        self.pos..self.pos + self.peek().map_or(0, char::len_utf8)
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            Ok(())
+        } else {
+            Err(ParseError::new(
+                ParseErrorKind::ExpectedCharacter(c),
+                self.next_range(),
+            ))
+        }
+    }
+
+    /// Reads the elements of an array or object until the closing character, calling `element` for each one.
+    fn elements(
+        &mut self,
+        open: char,
+        close: char,
+        mut element: impl FnMut(&mut Self) -> Result<(), ParseError>,
+    ) -> Result<(), ParseError> {
+        self.depth += 1;
+        if self.depth > MAX_JSON_DEPTH {
+            return Err(ParseError::new(
+                ParseErrorKind::NestedTooDeep(MAX_JSON_DEPTH),
+                self.next_range(),
+            ));
+        }
+        self.expect(open)?;
+        self.skip_whitespace();
+
+        if self.peek() != Some(close) {
+            loop {
+                element(self)?;
+                self.skip_whitespace();
+                if self.peek() != Some(',') {
+                    break;
+                }
+                self.pos += 1;
+            }
+        }
+
+        self.expect(close)?;
+        self.depth -= 1;
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Json, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                let mut entries = IndexMap::new();
+                self.elements('{', '}', |reader| {
+                    reader.skip_whitespace();
+                    if reader.peek() != Some('"') {
+                        return Err(ParseError::new(
+                            ParseErrorKind::ExpectedCharacter('"'),
+                            reader.next_range(),
+                        ));
+                    }
+                    let key = reader.string()?;
+                    reader.expect(':')?;
+                    entries.insert(key, reader.value()?);
+                    Ok(())
+                })?;
+                Ok(Json::Object(entries))
+            }
+            Some('[') => {
+                let mut elements = Vec::new();
+                self.elements('[', ']', |reader| {
+                    elements.push(reader.value()?);
+                    Ok(())
+                })?;
+                Ok(Json::Array(elements))
+            }
+            Some('"') => self.string().map(Json::String),
+            Some('-' | '0'..='9') => self.number(),
+            _ => {
+                let rest = &self.input[self.pos..];
+                let literal = [
+                    ("null", Json::Null),
+                    ("true", Json::Bool(true)),
+                    ("false", Json::Bool(false)),
+                ]
+                .iter()
+                .find(|(word, _)| rest.starts_with(word))
+                .cloned();
+
+                match literal {
+                    Some((word, value)) => {
+                        self.pos += word.len();
+                        Ok(value)
+                    }
+                    None => Err(ParseError::new(
+                        ParseErrorKind::ExpectedJsonValue,
+                        self.next_range(),
+                    )),
+                }
+            }
+        }
+    }
+
+    /// Reads a number like `-1.5e3`, following the exact JSON grammar.
+    fn number(&mut self) -> Result<Json, ParseError> {
+        let start = self.pos;
+        let bytes = self.input.as_bytes();
+        let digits = |pos: &mut usize| {
+            let begin = *pos;
+            while bytes.get(*pos).is_some_and(u8::is_ascii_digit) {
+                *pos += 1;
+            }
+            *pos > begin
+        };
+
+        let mut pos = start;
+        if bytes[pos] == b'-' {
+            pos += 1;
+        }
+        let int_start = pos;
+        // A number can't have leading zeros, like `05`.
+        let mut valid = digits(&mut pos) && (bytes[int_start] != b'0' || pos - int_start == 1);
+        if valid && bytes.get(pos) == Some(&b'.') {
+            pos += 1;
+            valid = digits(&mut pos);
+        }
+        if valid && matches!(bytes.get(pos), Some(b'e' | b'E')) {
+            pos += 1;
+            if matches!(bytes.get(pos), Some(b'+' | b'-')) {
+                pos += 1;
+            }
+            valid = digits(&mut pos);
+        }
+
+        // Include the rest of the number in the error, like the `5` of `05`.
+        while bytes
+            .get(pos)
+            .is_some_and(|b| b.is_ascii_alphanumeric() || b"+-.".contains(b))
+        {
+            valid = false;
+            pos += 1;
+        }
+
+        self.pos = pos;
+        match self.input[start..pos].parse() {
+            Ok(number) if valid => Ok(Json::Number(number)),
+            _ => Err(ParseError::new(
+                ParseErrorKind::InvalidJsonNumber,
+                start..pos,
+            )),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        let mut string = String::new();
+        let mut chars = self.input[start..].char_indices().skip(1);
+
+        // Reads the 4 hex digits after `\u`.
+        let input = self.input;
+        let hex = |at: usize| {
+            input
+                .get(at..at + 4)
+                .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+                .and_then(|digits| u16::from_str_radix(digits, 16).ok())
+        };
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos = start + i + 1;
+                    return Ok(string);
+                }
+                '\\' => {
+                    let escaped = match chars.next() {
+                        Some((_, c @ ('"' | '\\' | '/'))) => c,
+                        Some((_, 'b')) => '\u{8}',
+                        Some((_, 'f')) => '\u{c}',
+                        Some((_, 'n')) => '\n',
+                        Some((_, 'r')) => '\r',
+                        Some((_, 't')) => '\t',
+                        Some((j, 'u')) => {
+                            let unit = hex(start + j + 1);
+                            // Characters outside of the basic plane are written as a pair of surrogates.
+                            let low = match unit {
+                                Some(0xD800..=0xDBFF)
+                                    if input[start + j + 5..].starts_with("\\u") =>
+                                {
+                                    hex(start + j + 7)
+                                }
+                                _ => None,
+                            };
+                            let (c, len) = match (unit, low) {
+                                (Some(high), Some(low @ 0xDC00..=0xDFFF)) => (
+                                    char::decode_utf16([high, low]).next().and_then(Result::ok),
+                                    10,
+                                ),
+                                (Some(unit), _) => (char::from_u32(unit as u32), 4),
+                                (None, _) => (None, 0),
+                            };
+                            match c {
+                                Some(c) => {
+                                    for _ in 0..len {
+                                        chars.next();
+                                    }
+                                    c
+                                }
+                                None => {
+                                    return Err(ParseError::new(
+                                        ParseErrorKind::InvalidEscape('u'),
+                                        start + i..(start + j + 5).min(input.len()),
+                                    ));
+                                }
+                            }
+                        }
+                        Some((j, c)) => {
+                            return Err(ParseError::new(
+                                ParseErrorKind::InvalidEscape(c),
+                                start + i..start + j + c.len_utf8(),
+                            ));
+                        }
+                        None => break,
+                    };
+                    string.push(escaped);
+                }
+                c => string.push(c),
+            }
+        }
+
+        Err(ParseError::new(
+            ParseErrorKind::UnterminatedString,
+            start..self.input.len(),
+        ))
+    }
+}
+
+/// Parses a whole string as a single value.
+impl FromStr for Json {
+    type Err = ParseError;
+
+    fn from_str(string: &str) -> Result<Self, ParseError> {
+        let (value, len) = Json::parse_prefix(string)?;
+        let rest = string[len..].trim_start();
+        if !rest.is_empty() {
+            return Err(ParseError::new(
+                ParseErrorKind::TooManyArguments,
+                string.len() - rest.len()..string.len(),
+            ));
+        }
+        Ok(value)
+    }
+}
+
+/// Writes the value as compact JSON.
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn string(f: &mut fmt::Formatter<'_>, string: &str) -> fmt::Result {
+            write!(f, "\"")?;
+            for c in string.chars() {
+                match c {
+                    '"' => write!(f, "\\\"")?,
+                    '\\' => write!(f, "\\\\")?,
+                    '\n' => write!(f, "\\n")?,
+                    '\r' => write!(f, "\\r")?,
+                    '\t' => write!(f, "\\t")?,
+                    c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+                    c => write!(f, "{}", c)?,
+                }
+            }
+            write!(f, "\"")
+        }
+
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(number) => write!(f, "{}", number),
+            Json::String(s) => string(f, s),
+            Json::Array(elements) => {
+                write!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// An argument holding exactly one JSON value. Unlike [`StringWildcard`](super::StringWildcard) it stops at
+/// the end of the value, so more arguments can follow it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonArgument {
+    value: Json,
+}
+
+impl JsonArgument {
+    pub fn new(value: Json) -> Self {
+        Self { value }
+    }
+
+    pub fn get(&self) -> &Json {
+        &self.value
+    }
+
+    pub fn get_mut(&mut self) -> &mut Json {
+        &mut self.value
+    }
+
+    pub fn into_inner(self) -> Json {
+        self.value
+    }
+}
+
+/// The regex of any JSON value. It is a superset, as the grammar can't be expressed exactly by a regex.
+pub(crate) const JSON_REGEX: &str =
+    r#"(\{.*\}|\[.*\]|"([^"\\]|\\.)*"|true|false|null|-?[0-9][0-9.eE+\-]*)"#;
+
+#[derive(Default)]
+pub struct JsonParser;
+
+impl IterParser for JsonParser {
+    type Extract = (JsonArgument,);
+    type ParserState = ();
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        _state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        if input.is_empty() {
+            return (
+                Err(ParseError::at_token(ParseErrorKind::EmptyInput, input).in_argument(0)),
+                None,
+            );
+        }
+
+        match Json::parse_prefix(input) {
+            Ok((value, len)) => (Ok(((JsonArgument { value },), &input[len..])), None),
+            Err(err) => (Err(err.in_argument(0)), None),
+        }
+    }
+
+    fn regex(&self) -> String {
+        JSON_REGEX.into()
+    }
+
+    fn usage(&self) -> String {
+        String::from("<json>")
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        vec![vec![NodeKind::argument(
+            &self.usage(),
+            ArgumentParser::Component,
+        )]]
+    }
+}
+
+impl Argument for JsonArgument {
+    type Parser = JsonParser;
+    type ParserState = ();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::builder::{literal, CommandBuilder};
+    use crate::command::{Command, CommandSpec};
+    use crate::regex::NFA;
+
+    #[test]
+    fn values() {
+        let value: Json = r#" {"a": [1, -2.5e1, true, null], "b\n": "\u00e9\ud83d\ude00\/"} "#
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            value.get("a"),
+            Some(&Json::Array(vec![
+                Json::Number(1.0),
+                Json::Number(-25.0),
+                Json::Bool(true),
+                Json::Null
+            ]))
+        );
+        assert_eq!(value.get("b\n").and_then(Json::as_str), Some("é😀/"));
+        assert_eq!(
+            value.to_string(),
+            "{\"a\":[1,-25,true,null],\"b\\n\":\"é😀/\"}"
+        );
+        assert_eq!(value.to_string().parse::<Json>().unwrap(), value);
+    }
+
+    #[test]
+    fn errors() {
+        let err = |input: &str| input.parse::<Json>().unwrap_err();
+
+        assert_eq!(err("[1,]").kind, ParseErrorKind::ExpectedJsonValue);
+        assert_eq!(err("[1,]").range, 3..4);
+        assert_eq!(err("{a:1}").kind, ParseErrorKind::ExpectedCharacter('"'));
+        assert_eq!(
+            err("{\"a\" 1}").kind,
+            ParseErrorKind::ExpectedCharacter(':')
+        );
+        assert_eq!(err("[1 2]").kind, ParseErrorKind::ExpectedCharacter(']'));
+        assert_eq!(err("[1 2]").range, 3..4);
+        assert_eq!(err("[05]").kind, ParseErrorKind::InvalidJsonNumber);
+        assert_eq!(err("[05]").range, 1..3);
+        assert_eq!(err("1.").kind, ParseErrorKind::InvalidJsonNumber);
+        assert_eq!(err("\"a\\x\"").kind, ParseErrorKind::InvalidEscape('x'));
+        assert_eq!(err("\"a\\x\"").range, 2..4);
+        assert_eq!(err("\"\\u12\"").kind, ParseErrorKind::InvalidEscape('u'));
+        assert_eq!(err("\"abc").kind, ParseErrorKind::UnterminatedString);
+        assert_eq!(err("nul").kind, ParseErrorKind::ExpectedJsonValue);
+
+        let deep = "[".repeat(MAX_JSON_DEPTH + 1);
+        assert_eq!(
+            err(&deep).kind,
+            ParseErrorKind::NestedTooDeep(MAX_JSON_DEPTH)
+        );
+    }
+
+    #[test]
+    fn argument() {
+        let command: CommandSpec<(), String, _, _, _> = literal("/data")
+            .space()
+            .arg::<JsonArgument>()
+            .space()
+            .arg::<u32>()
+            .on_call(|json: JsonArgument, count: u32| move || format!("{} {}", json.get(), count));
+
+        assert_eq!(
+            command.call((), r#"/data {"a": "b c"} 3"#).unwrap(),
+            r#"{"a":"b c"} 3"#
+        );
+        assert_eq!(command.call((), "/data [1, 2] 3").unwrap(), "[1,2] 3");
+
+        let err = command.call((), r#"/data {"a": "b c", } 3"#).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::ExpectedCharacter('"'));
+        assert_eq!(err.range, 19..20);
+        assert_eq!(err.argument, Some(0));
+
+        assert!(NFA::<usize>::regex(&JsonParser.regex()).is_ok());
+    }
+}
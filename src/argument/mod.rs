@@ -1,14 +1,33 @@
 mod boolean;
 mod choice;
+mod coordinates;
+mod entity_selector;
+mod enums;
+mod json;
 mod numbers;
+mod quoted_string;
+mod ranges;
+mod resource_location;
+mod snbt;
 mod string_wildcard;
 mod strings;
+mod text_component;
 
 use crate::parser::IterParser;
 pub use choice::*;
+pub use coordinates::*;
+pub use entity_selector::*;
+pub use enums::*;
+pub use json::*;
+pub use lieutenant_derive::Argument;
 pub use numbers::*;
+pub use quoted_string::*;
+pub use ranges::*;
+pub use resource_location::*;
+pub use snbt::*;
 pub use string_wildcard::*;
 pub use strings::*;
+pub use text_component::*;
 
 pub trait Argument {
     type Parser: IterParser<Extract = (Self,), ParserState = Self::ParserState> + Sized + Default;
@@ -1,14 +1,186 @@
 use super::Argument;
-use crate::parser::IterParser;
+use std::{
+    convert::TryFrom,
+    fmt::Display,
+    marker::PhantomData,
+    num::{
+        IntErrorKind, NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize,
+        NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, ParseIntError,
+    },
+    ops::{Bound, RangeBounds},
+    str::FromStr,
+};
 
-#[derive(Default)]
-pub struct U32Parser {}
+use crate::graph::{ArgumentParser, NodeKind};
+use crate::parser::{IterParser, ParseError, ParseErrorKind};
+
+/// The integer types we can parse, including the `NonZero` ones.
+pub trait Integer: Copy + PartialOrd + Display + FromStr<Err = ParseIntError> {
+    const SIGNED: bool;
+    const MIN: Self;
+    const MAX: Self;
+    const NAME: &'static str;
+
+    fn checked_next(self) -> Option<Self>;
+    fn checked_prev(self) -> Option<Self>;
+
+    /// Returns the value if it fits in an `i64`, which is the largest integer Brigadier supports.
+    fn to_i64(self) -> Option<i64>;
+}
+
+macro_rules! integer {
+    ($type:ty, $signed:expr, $name:expr) => {
+        impl Integer for $type {
+            const SIGNED: bool = $signed;
+            const MIN: Self = <$type>::MIN;
+            const MAX: Self = <$type>::MAX;
+            const NAME: &'static str = $name;
+
+            fn checked_next(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+
+            fn checked_prev(self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+
+            fn to_i64(self) -> Option<i64> {
+                i64::try_from(self).ok()
+            }
+        }
+
+        impl Argument for $type {
+            type Parser = IntegerParser<$type>;
+            type ParserState = ();
+        }
+    };
+    ($type:ty, $inner:ty, $signed:expr, $name:expr) => {
+        impl Integer for $type {
+            const SIGNED: bool = $signed;
+            const MIN: Self = <$type>::MIN;
+            const MAX: Self = <$type>::MAX;
+            const NAME: &'static str = $name;
+
+            fn checked_next(self) -> Option<Self> {
+                // Skip over zero, since it is not a valid value.
+                match self.get().checked_add(1)? {
+                    0 => <$type>::new(self.get().checked_add(2)?),
+                    next => <$type>::new(next),
+                }
+            }
+
+            fn checked_prev(self) -> Option<Self> {
+                match self.get().checked_sub(1)? {
+                    0 => <$type>::new(self.get().checked_sub(2)?),
+                    prev => <$type>::new(prev),
+                }
+            }
+
+            fn to_i64(self) -> Option<i64> {
+                i64::try_from(self.get()).ok()
+            }
+        }
+
+        impl Argument for $type {
+            type Parser = IntegerParser<$type>;
+            type ParserState = ();
+        }
+    };
+}
+
+integer!(i8, true, "i8");
+integer!(i16, true, "i16");
+integer!(i32, true, "i32");
+integer!(i64, true, "i64");
+integer!(i128, true, "i128");
+integer!(isize, true, "isize");
+integer!(u8, false, "u8");
+integer!(u16, false, "u16");
+integer!(u32, false, "u32");
+integer!(u64, false, "u64");
+integer!(u128, false, "u128");
+integer!(usize, false, "usize");
+integer!(NonZeroI8, i8, true, "i8");
+integer!(NonZeroI16, i16, true, "i16");
+integer!(NonZeroI32, i32, true, "i32");
+integer!(NonZeroI64, i64, true, "i64");
+integer!(NonZeroI128, i128, true, "i128");
+integer!(NonZeroIsize, isize, true, "isize");
+integer!(NonZeroU8, u8, false, "u8");
+integer!(NonZeroU16, u16, false, "u16");
+integer!(NonZeroU32, u32, false, "u32");
+integer!(NonZeroU64, u64, false, "u64");
+integer!(NonZeroU128, u128, false, "u128");
+integer!(NonZeroUsize, usize, false, "usize");
+
+/// Parses an integer, only accepting values between min and max, both inclusive.
+pub struct IntegerParser<T> {
+    min: T,
+    max: T,
+}
+
+pub type U32Parser = IntegerParser<u32>;
+
+impl<T: Integer> IntegerParser<T> {
+    /// Creates a parser only accepting values in the range.
+    /// Panics if the range does not contain any values.
+    pub fn new<R: RangeBounds<T>>(range: R) -> Self {
+        let min = match range.start_bound() {
+            Bound::Included(min) => Some(*min),
+            Bound::Excluded(min) => min.checked_next(),
+            Bound::Unbounded => Some(T::MIN),
+        };
+        let max = match range.end_bound() {
+            Bound::Included(max) => Some(*max),
+            Bound::Excluded(max) => max.checked_prev(),
+            Bound::Unbounded => Some(T::MAX),
+        };
+
+        match (min, max) {
+            (Some(min), Some(max)) if min <= max => Self { min, max },
+            _ => panic!("The range of a {} argument can't be empty", T::NAME),
+        }
+    }
+
+    pub fn min(&self) -> T {
+        self.min
+    }
+
+    pub fn max(&self) -> T {
+        self.max
+    }
+
+    fn out_of_range(&self, len: usize) -> ParseError {
+        ParseError::new(
+            ParseErrorKind::OutOfRange {
+                min: self.min.to_string(),
+                max: self.max.to_string(),
+            },
+            0..len,
+        )
+        .in_argument(0)
+    }
+}
+
+impl<T: Integer> Default for IntegerParser<T> {
+    fn default() -> Self {
+        Self {
+            min: T::MIN,
+            max: T::MAX,
+        }
+    }
+}
 
 #[derive(Default)]
-pub struct F32Parser;
+pub struct FloatParser<T> {
+    float: PhantomData<T>,
+}
+
+pub type F32Parser = FloatParser<f32>;
+pub type F64Parser = FloatParser<f64>;
 
-impl IterParser for U32Parser {
-    type Extract = (u32,);
+impl<T: Integer> IterParser for IntegerParser<T> {
+    type Extract = (T,);
 
     type ParserState = ();
 
@@ -18,41 +190,105 @@ impl IterParser for U32Parser {
         _state: Self::ParserState,
         input: &'p str,
     ) -> (
-        anyhow::Result<(Self::Extract, &'p str)>,
+        Result<(Self::Extract, &'p str), ParseError>,
         Option<Self::ParserState>,
     ) {
-        // Consume digit from head of input
-
-        let mut iter = input.char_indices();
-        let mut index = 0;
-        if let Some((i, c)) = iter.next() {
-            if c == '+' || c == '-' {
-                index = i;
+        // Consume sign and digits from head of input
+        let sign = match input.as_bytes().first() {
+            None => {
+                return (
+                    Err(ParseError::at_token(ParseErrorKind::EmptyInput, input).in_argument(0)),
+                    None,
+                );
             }
-        } else {
-            return (Err(anyhow::anyhow!("Empty input")), None);
+            Some(b'+') => 1,
+            Some(b'-') if T::SIGNED => 1,
+            Some(_) => 0,
+        };
+
+        let digits = input[sign..]
+            .bytes()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if digits == 0 {
+            return (
+                Err(ParseError::at_token(ParseErrorKind::InvalidNumber, input).in_argument(0)),
+                None,
+            );
         }
 
-        for (i, c) in iter {
-            if !c.is_ascii_digit() {
-                break;
+        let end = sign + digits;
+        match input[..end].parse::<T>() {
+            Ok(number) if number < self.min || number > self.max => {
+                (Err(self.out_of_range(end)), None)
+            }
+            Ok(number) => (Ok(((number,), &input[end..])), None),
+            Err(e) => {
+                let err = match e.kind() {
+                    IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => self.out_of_range(end),
+                    IntErrorKind::Zero => {
+                        ParseError::new(ParseErrorKind::Zero, 0..end).in_argument(0)
+                    }
+                    _ => ParseError::at_token(ParseErrorKind::InvalidNumber, input).in_argument(0),
+                };
+                (Err(err), None)
             }
-            index = i
         }
+    }
 
-        match input[0..=index].parse::<u32>() {
-            Ok(number) => (Ok(((number,), &input[index + 1..input.len()])), None),
-            Err(_) => (Err(anyhow::anyhow!("Not a number")), None),
+    fn regex(&self) -> String {
+        // We only parse ascii digits, and [0-9] gives a much smaller automaton than the unicode aware \d.
+        if T::SIGNED {
+            "[+\\-]?[0-9]+".into()
+        } else {
+            "\\+?[0-9]+".into()
         }
     }
 
-    fn regex(&self) -> String {
-        "[\\+|-]?\\d+".into()
+    fn usage(&self) -> String {
+        format!("<{}>", T::NAME)
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        let min = self.min.to_i64();
+        let max = self.max.to_i64();
+        let fits_i32 = |bound: Option<i64>| bound.is_some_and(|b| i32::try_from(b).is_ok());
+
+        // Types that fit in an i32 are sent as integers, so clients don't accept values we would reject.
+        let parser = if fits_i32(T::MIN.to_i64()) && fits_i32(T::MAX.to_i64()) {
+            ArgumentParser::Integer {
+                min: min.map(|min| min as i32),
+                max: max.map(|max| max as i32),
+            }
+        } else {
+            ArgumentParser::Long { min, max }
+        };
+        vec![vec![NodeKind::argument(&self.usage(), parser)]]
     }
 }
 
-impl IterParser for F32Parser {
-    type Extract = (f32,);
+/// The floating point types we can parse.
+pub trait Float: FromStr {
+    /// The Brigadier parser the type is sent to clients as.
+    const BRIGADIER: ArgumentParser;
+}
+
+impl Float for f32 {
+    const BRIGADIER: ArgumentParser = ArgumentParser::Float {
+        min: None,
+        max: None,
+    };
+}
+
+impl Float for f64 {
+    const BRIGADIER: ArgumentParser = ArgumentParser::Double {
+        min: None,
+        max: None,
+    };
+}
+
+impl<T: Float> IterParser for FloatParser<T> {
+    type Extract = (T,);
     type ParserState = ();
 
     fn parse<'p>(
@@ -60,20 +296,23 @@ impl IterParser for F32Parser {
         _state: Self::ParserState,
         input: &'p str,
     ) -> (
-        anyhow::Result<(Self::Extract, &'p str)>,
+        Result<(Self::Extract, &'p str), ParseError>,
         Option<Self::ParserState>,
     ) {
         let string = input.trim();
 
         if string.is_empty() {
-            return (Err(anyhow::anyhow!("Empty input")), None);
+            return (
+                Err(ParseError::at_token(ParseErrorKind::EmptyInput, input).in_argument(0)),
+                None,
+            );
         }
         let pos = string.find(' ').unwrap_or(string.len());
 
-        return match string[..pos].parse::<f32>() {
+        return match string[..pos].parse::<T>() {
             Ok(f) => (Ok(((f,), &string[pos..])), None),
-            Err(e) => (
-                Err(anyhow::anyhow!("Invalid input (failed to parse: {e})")),
+            Err(_) => (
+                Err(ParseError::at_token(ParseErrorKind::InvalidNumber, input).in_argument(0)),
                 None,
             ),
         };
@@ -83,11 +322,14 @@ impl IterParser for F32Parser {
         r"[+-]?([0-9]*[.])?[0-9]+".into() // See https://stackoverflow.com/a/12643073.
                                           // As far as i understand, this solution is not vulnerable against ReDoS
     }
-}
 
-impl Argument for u32 {
-    type Parser = U32Parser;
-    type ParserState = ();
+    fn usage(&self) -> String {
+        format!("<{}>", std::any::type_name::<T>())
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        vec![vec![NodeKind::argument(&self.usage(), T::BRIGADIER)]]
+    }
 }
 
 impl Argument for f32 {
@@ -95,10 +337,138 @@ impl Argument for f32 {
     type ParserState = ();
 }
 
+impl Argument for f64 {
+    type Parser = F64Parser;
+    type ParserState = ();
+}
+
 #[cfg(test)]
 mod tests {
+    use super::IntegerParser;
     use crate::command::builder::{literal, CommandBuilder};
-    use crate::command::Command;
+    use crate::command::{Command, CommandSpec};
+    use crate::parser::{IterParser, ParseErrorKind};
+    use std::num::NonZeroU8;
+
+    #[test]
+    fn signed() {
+        let command: CommandSpec<(i64,), i64, _, _, _> = literal("/add")
+            .space()
+            .arg::<i8>()
+            .on_call(|x: i8| move |state: i64| state + x as i64);
+
+        assert_eq!(command.call((10,), "/add -5").unwrap(), 5);
+        assert_eq!(command.call((10,), "/add +5").unwrap(), 15);
+        assert_eq!(command.call((10,), "/add 127").unwrap(), 137);
+        assert_eq!(command.call((10,), "/add -128").unwrap(), -118);
+
+        let err = command.call((10,), "/add 128").unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::OutOfRange {
+                min: String::from("-128"),
+                max: String::from("127")
+            }
+        );
+        assert_eq!(err.range, 5..8);
+        assert_eq!(
+            command.call((10,), "/add -").unwrap_err().kind,
+            ParseErrorKind::InvalidNumber
+        );
+    }
+
+    #[test]
+    fn unsigned() {
+        let command: CommandSpec<(u64,), u64, _, _, _> = literal("/add")
+            .space()
+            .arg::<u64>()
+            .on_call(|x: u64| move |state: u64| state + x);
+
+        assert_eq!(
+            command.call((1,), "/add 18446744073709551614").unwrap(),
+            u64::MAX
+        );
+        assert_eq!(
+            command.call((1,), "/add -1").unwrap_err().kind,
+            ParseErrorKind::InvalidNumber
+        );
+        assert_eq!(IntegerParser::<u64>::default().regex(), "\\+?[0-9]+");
+        assert_eq!(IntegerParser::<i64>::default().usage(), "<i64>");
+    }
+
+    #[test]
+    fn range() {
+        let command: CommandSpec<(i32,), i32, _, _, _> = literal("/setblock")
+            .space()
+            .arg_range::<i32>(-64..=320)
+            .on_call(|y: i32| move |_state: i32| y);
+
+        assert_eq!(command.call((0,), "/setblock -64").unwrap(), -64);
+        assert_eq!(command.call((0,), "/setblock 320").unwrap(), 320);
+
+        let err = command.call((0,), "/setblock 321").unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::OutOfRange {
+                min: String::from("-64"),
+                max: String::from("320")
+            }
+        );
+        assert_eq!(err.range, 10..13);
+        assert_eq!(err.argument, Some(0));
+        assert_eq!(
+            err.to_string(),
+            "Number must be between -64 and 320 at 10..13 (argument 0)"
+        );
+        assert!(command.call((0,), "/setblock -65").is_err());
+        // The regex doesn't know about the bounds, but still has to match every accepted value.
+        assert!(regex::Regex::new(&command.regex())
+            .unwrap()
+            .is_match("/setblock -64"));
+    }
+
+    #[test]
+    fn range_bounds() {
+        let parser = IntegerParser::<u8>::new(1..9);
+        assert_eq!((parser.min(), parser.max()), (1, 8));
+        let parser = IntegerParser::<u8>::new(..);
+        assert_eq!((parser.min(), parser.max()), (0, 255));
+
+        let parser = IntegerParser::<NonZeroU8>::new(..NonZeroU8::new(9).unwrap());
+        assert_eq!(parser.min().get(), 1);
+        assert_eq!(parser.max().get(), 8);
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_range() {
+        IntegerParser::<u8>::new(5..5);
+    }
+
+    #[test]
+    fn non_zero() {
+        let command: CommandSpec<(u8,), u8, _, _, _> = literal("/give")
+            .space()
+            .arg::<NonZeroU8>()
+            .on_call(|count: NonZeroU8| move |_state: u8| count.get());
+
+        assert_eq!(command.call((0,), "/give 64").unwrap(), 64);
+        assert_eq!(
+            command.call((0,), "/give 0").unwrap_err().kind,
+            ParseErrorKind::Zero
+        );
+    }
+
+    #[test]
+    fn test_f64() {
+        let command: CommandSpec<(f64,), f64, _, _, _> = literal("/scale")
+            .space()
+            .arg::<f64>()
+            .on_call(|x: f64| move |state: f64| state * x);
+
+        assert_eq!(command.call((2.0,), "/scale 0.25").unwrap(), 0.5);
+        assert!(command.call((2.0,), "/scale x").is_err());
+    }
 
     #[test]
     fn test_f32() {
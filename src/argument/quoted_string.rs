@@ -0,0 +1,195 @@
+use crate::argument::Argument;
+use crate::graph::{ArgumentParser, NodeKind, StringKind};
+use crate::parser::{IterParser, ParseError, ParseErrorKind};
+
+/// A single word, or a string in double or single quotes that may contain spaces.
+///
+/// Inside quotes, `\"`, `\'`, `\\` and `\n` are unescaped. This works like `StringArgumentType.string()`
+/// in Brigadier, except that bare words may contain any character that isn't whitespace or a quote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotedString {
+    string: String,
+}
+
+impl QuotedString {
+    pub fn new(string: String) -> Self {
+        Self { string }
+    }
+
+    pub fn get(&self) -> &str {
+        &self.string
+    }
+
+    pub fn get_mut(&mut self) -> &mut str {
+        &mut self.string
+    }
+
+    pub fn into_inner(self) -> String {
+        self.string
+    }
+}
+
+#[derive(Default)]
+pub struct QuotedStringParser {}
+
+impl QuotedStringParser {
+    /// Parses the quoted string at the start of the input, returning the unescaped string and the length it had
+    /// in the input, including the quotes.
+    pub(crate) fn parse_quoted(quote: char, input: &str) -> Result<(String, usize), ParseError> {
+        let mut string = String::new();
+        let mut chars = input.char_indices().skip(1);
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    let escaped = match chars.next() {
+                        Some((_, c @ ('"' | '\'' | '\\'))) => c,
+                        Some((_, 'n')) => '\n',
+                        Some((j, c)) => {
+                            return Err(ParseError::new(
+                                ParseErrorKind::InvalidEscape(c),
+                                i..j + c.len_utf8(),
+                            ));
+                        }
+                        None => break,
+                    };
+                    string.push(escaped);
+                }
+                c if c == quote => return Ok((string, i + c.len_utf8())),
+                c => string.push(c),
+            }
+        }
+
+        Err(ParseError::new(
+            ParseErrorKind::UnterminatedString,
+            0..input.len(),
+        ))
+    }
+}
+
+impl IterParser for QuotedStringParser {
+    type Extract = (QuotedString,);
+    type ParserState = ();
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        _state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        let result = match input.chars().next() {
+            None => Err(ParseError::at_token(ParseErrorKind::EmptyInput, input)),
+            Some(c) if c.is_whitespace() => {
+                Err(ParseError::at_token(ParseErrorKind::EmptyInput, input))
+            }
+            Some(quote @ ('"' | '\'')) => Self::parse_quoted(quote, input),
+            Some(_) => {
+                let end = input.find(char::is_whitespace).unwrap_or(input.len());
+                Ok((input[..end].to_string(), end))
+            }
+        };
+
+        match result {
+            Ok((string, end)) => (Ok(((QuotedString { string },), &input[end..])), None),
+            Err(err) => (Err(err.in_argument(0)), None),
+        }
+    }
+
+    fn regex(&self) -> String {
+        r#""([^"\\]|\\.)*"|'([^'\\]|\\.)*'|[^\s"']\S*"#.into()
+    }
+
+    fn usage(&self) -> String {
+        String::from("<\"string\">")
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        vec![vec![NodeKind::argument(
+            &self.usage(),
+            ArgumentParser::String(StringKind::QuotablePhrase),
+        )]]
+    }
+}
+
+impl Argument for QuotedString {
+    type Parser = QuotedStringParser;
+    type ParserState = ();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::argument::QuotedString;
+    use crate::command::builder::{literal, CommandBuilder};
+    use crate::command::{Command, CommandSpec};
+    use crate::parser::ParseErrorKind;
+    use crate::regex::NFA;
+
+    #[test]
+    fn quoted_and_bare() {
+        let command: CommandSpec<(), (String, u32), _, _, _> = literal("/say")
+            .space()
+            .arg::<QuotedString>()
+            .space()
+            .arg::<u32>()
+            .on_call(|message: QuotedString, times: u32| {
+                move || (message.get().to_string(), times)
+            });
+
+        assert_eq!(
+            command.call((), "/say \"Hello world\" 2").unwrap(),
+            (String::from("Hello world"), 2)
+        );
+        assert_eq!(
+            command.call((), "/say 'Hello \"world\"' 2").unwrap(),
+            (String::from("Hello \"world\""), 2)
+        );
+        assert_eq!(
+            command.call((), "/say hello 2").unwrap(),
+            (String::from("hello"), 2)
+        );
+        assert_eq!(command.call((), "/say \"\" 2").unwrap(), (String::new(), 2));
+    }
+
+    #[test]
+    fn escapes() {
+        let command: CommandSpec<(), String, _, _, _> = literal("/say")
+            .space()
+            .arg::<QuotedString>()
+            .on_call(|message: QuotedString| move || message.get().to_string());
+
+        assert_eq!(
+            command.call((), r#"/say "a \"b\" \\ c\nd""#).unwrap(),
+            "a \"b\" \\ c\nd"
+        );
+        assert_eq!(command.call((), r#"/say 'it\'s'"#).unwrap(), "it's");
+
+        let err = command.call((), r#"/say "a \x""#).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidEscape('x'));
+        assert_eq!(err.range, 8..10);
+        assert_eq!(err.argument, Some(0));
+
+        let err = command.call((), "/say \"Hello world").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnterminatedString);
+        assert_eq!(err.range, 5..17);
+
+        assert!(command.call((), "/say \"a\"b").is_err());
+    }
+
+    #[test]
+    fn regex() {
+        let command: CommandSpec<(), String, _, _, _> = literal("/say")
+            .space()
+            .arg::<QuotedString>()
+            .on_call(|message: QuotedString| move || message.get().to_string());
+        let regex = regex::Regex::new(&format!("^({})$", command.regex())).unwrap();
+
+        assert!(regex.is_match(r#"/say "a \"b\" c""#));
+        assert!(regex.is_match("/say 'a b'"));
+        assert!(regex.is_match("/say word"));
+        assert!(!regex.is_match("/say a b"));
+        assert!(NFA::<usize>::regex(&command.regex()).is_ok());
+    }
+}
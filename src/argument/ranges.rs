@@ -0,0 +1,263 @@
+use std::{marker::PhantomData, str::FromStr};
+
+use super::Argument;
+use crate::graph::{ArgumentParser, NodeKind};
+use crate::parser::{IterParser, ParseError, ParseErrorKind};
+
+/// A range of integers like `1..5`, `..10`, `3..` or `3`. Both ends are inclusive, and at least one is given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntRange {
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+}
+
+/// A range of numbers like `0.5..5`, `..10`, `3..` or `3`. Both ends are inclusive, and at least one is given.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloatRange {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl IntRange {
+    pub fn contains(&self, value: i32) -> bool {
+        self.min.map_or(true, |min| min <= value) && self.max.map_or(true, |max| value <= max)
+    }
+}
+
+impl FloatRange {
+    pub fn contains(&self, value: f64) -> bool {
+        self.min.map_or(true, |min| min <= value) && self.max.map_or(true, |max| value <= max)
+    }
+}
+
+/// Parses the bounds of a range, where a single number is both the min and the max.
+fn parse_bounds<T>(string: &str) -> Result<(Option<T>, Option<T>), ParseErrorKind>
+where
+    T: FromStr + PartialOrd + Copy,
+{
+    // Only allow the characters of plain decimal numbers, so `inf`, `NaN` and exponents are rejected.
+    let bound = |s: &str| match s {
+        "" => Ok(None),
+        s if s.bytes().all(|b| b.is_ascii_digit() || b"+-.".contains(&b)) => s
+            .parse()
+            .map(Some)
+            .map_err(|_| ParseErrorKind::InvalidRange),
+        _ => Err(ParseErrorKind::InvalidRange),
+    };
+
+    let (min, max) = match string.split_once("..") {
+        Some((min, max)) => (bound(min)?, bound(max)?),
+        None => {
+            let value = bound(string)?;
+            (value, value)
+        }
+    };
+
+    match (min, max) {
+        (None, None) => Err(ParseErrorKind::InvalidRange),
+        (Some(min), Some(max)) if min > max => Err(ParseErrorKind::InvalidRange),
+        (min, max) => Ok((min, max)),
+    }
+}
+
+impl FromStr for IntRange {
+    type Err = ParseErrorKind;
+
+    fn from_str(string: &str) -> Result<Self, ParseErrorKind> {
+        let (min, max) = parse_bounds(string)?;
+        Ok(IntRange { min, max })
+    }
+}
+
+impl FromStr for FloatRange {
+    type Err = ParseErrorKind;
+
+    fn from_str(string: &str) -> Result<Self, ParseErrorKind> {
+        let (min, max) = parse_bounds(string)?;
+        Ok(FloatRange { min, max })
+    }
+}
+
+/// The range types we can parse.
+pub trait NumberRange: FromStr<Err = ParseErrorKind> {
+    const NAME: &'static str;
+    /// The regex of a single bound.
+    const NUMBER_REGEX: &'static str;
+    const BRIGADIER: ArgumentParser;
+}
+
+impl NumberRange for IntRange {
+    const NAME: &'static str = "int_range";
+    const NUMBER_REGEX: &'static str = r"[+\-]?[0-9]+";
+    const BRIGADIER: ArgumentParser = ArgumentParser::IntRange;
+}
+
+impl NumberRange for FloatRange {
+    const NAME: &'static str = "float_range";
+    const NUMBER_REGEX: &'static str = r"[+\-]?([0-9]+(\.[0-9]*)?|\.[0-9]+)";
+    const BRIGADIER: ArgumentParser = ArgumentParser::FloatRange;
+}
+
+pub struct RangeParser<T> {
+    range: PhantomData<T>,
+}
+
+impl<T> Default for RangeParser<T> {
+    fn default() -> Self {
+        Self { range: PhantomData }
+    }
+}
+
+pub type IntRangeParser = RangeParser<IntRange>;
+pub type FloatRangeParser = RangeParser<FloatRange>;
+
+impl<T: NumberRange> IterParser for RangeParser<T> {
+    type Extract = (T,);
+    type ParserState = ();
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        _state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        let end = input.find(char::is_whitespace).unwrap_or(input.len());
+        if end == 0 {
+            return (
+                Err(ParseError::at_token(ParseErrorKind::EmptyInput, input).in_argument(0)),
+                None,
+            );
+        }
+
+        match input[..end].parse::<T>() {
+            Ok(range) => (Ok(((range,), &input[end..])), None),
+            Err(kind) => (Err(ParseError::new(kind, 0..end).in_argument(0)), None),
+        }
+    }
+
+    fn regex(&self) -> String {
+        let number = T::NUMBER_REGEX;
+        format!(r"({n}(\.\.({n})?)?|\.\.{n})", n = number)
+    }
+
+    fn usage(&self) -> String {
+        format!("<{}>", T::NAME)
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        vec![vec![NodeKind::argument(&self.usage(), T::BRIGADIER)]]
+    }
+}
+
+impl Argument for IntRange {
+    type Parser = IntRangeParser;
+    type ParserState = ();
+}
+
+impl Argument for FloatRange {
+    type Parser = FloatRangeParser;
+    type ParserState = ();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::builder::{literal, CommandBuilder};
+    use crate::command::{Command, CommandSpec};
+
+    #[test]
+    fn int_range() {
+        assert_eq!(
+            "1..5".parse(),
+            Ok(IntRange {
+                min: Some(1),
+                max: Some(5)
+            })
+        );
+        assert_eq!(
+            "..-10".parse(),
+            Ok(IntRange {
+                min: None,
+                max: Some(-10)
+            })
+        );
+        assert_eq!(
+            "3..".parse(),
+            Ok(IntRange {
+                min: Some(3),
+                max: None
+            })
+        );
+        assert_eq!(
+            "3".parse(),
+            Ok(IntRange {
+                min: Some(3),
+                max: Some(3)
+            })
+        );
+        assert!("..".parse::<IntRange>().is_err());
+        assert!("5..1".parse::<IntRange>().is_err());
+        assert!("1.5".parse::<IntRange>().is_err());
+
+        let range: IntRange = "..5".parse().unwrap();
+        assert!(range.contains(-100));
+        assert!(range.contains(5));
+        assert!(!range.contains(6));
+    }
+
+    #[test]
+    fn float_range() {
+        assert_eq!(
+            "0.5..1".parse(),
+            Ok(FloatRange {
+                min: Some(0.5),
+                max: Some(1.0)
+            })
+        );
+        assert_eq!(
+            "..0.5".parse(),
+            Ok(FloatRange {
+                min: None,
+                max: Some(0.5)
+            })
+        );
+        assert!("inf..".parse::<FloatRange>().is_err());
+        assert!("1e5".parse::<FloatRange>().is_err());
+    }
+
+    #[test]
+    fn argument() {
+        let command: CommandSpec<(i32,), bool, _, _, _> = literal("/matches")
+            .space()
+            .arg::<IntRange>()
+            .on_call(|range: IntRange| move |score: i32| range.contains(score));
+
+        assert!(command.call((3,), "/matches 1..5").unwrap());
+        assert!(!command.call((3,), "/matches 4..").unwrap());
+
+        let err = command.call((3,), "/matches 5..1").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidRange);
+        assert_eq!(err.range, 9..13);
+        assert_eq!(err.argument, Some(0));
+    }
+
+    #[test]
+    fn regex() {
+        let int = regex::Regex::new(&format!("^({})$", IntRangeParser::default().regex())).unwrap();
+        let float =
+            regex::Regex::new(&format!("^({})$", FloatRangeParser::default().regex())).unwrap();
+
+        for range in ["1", "1..5", "..5", "-3..", "+1..-1"] {
+            assert!(int.is_match(range), "{}", range);
+        }
+        assert!(!int.is_match(".."));
+        assert!(!int.is_match("1.5"));
+        for range in ["1.5", "1..5", "..0.5", ".5..1.", "1.5..2"] {
+            assert!(float.is_match(range), "{}", range);
+        }
+        assert!(!float.is_match("..."));
+    }
+}
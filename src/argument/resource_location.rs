@@ -0,0 +1,292 @@
+use std::{collections::BTreeSet, fmt, iter::FromIterator, str::FromStr, sync::Arc};
+
+use super::Argument;
+use crate::graph::{ArgumentParser, NodeKind};
+use crate::parser::{IterParser, ParseError, ParseErrorKind, Suggestion};
+
+/// A namespaced id like `minecraft:chicken`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ResourceLocation {
+    namespace: String,
+    path: String,
+}
+
+impl ResourceLocation {
+    /// Creates the id, if the namespace only contains `a-z0-9_.-` and the path only contains `a-z0-9_.-/`.
+    pub fn new(namespace: &str, path: &str) -> Result<Self, ParseErrorKind> {
+        let valid = |s: &str, extra: &[u8]| {
+            !s.is_empty()
+                && s.bytes().all(|b| {
+                    b.is_ascii_lowercase()
+                        || b.is_ascii_digit()
+                        || b"_.-".contains(&b)
+                        || extra.contains(&b)
+                })
+        };
+
+        if valid(namespace, b"") && valid(path, b"/") {
+            Ok(Self {
+                namespace: namespace.to_string(),
+                path: path.to_string(),
+            })
+        } else {
+            Err(ParseErrorKind::InvalidResourceLocation)
+        }
+    }
+
+    /// Parses `namespace:path`, or just `path` using the default namespace.
+    pub fn parse_with_namespace(
+        string: &str,
+        default_namespace: &str,
+    ) -> Result<Self, ParseErrorKind> {
+        match string.split_once(':') {
+            Some(("", path)) => Self::new(default_namespace, path),
+            Some((namespace, path)) => Self::new(namespace, path),
+            None => Self::new(default_namespace, string),
+        }
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl fmt::Display for ResourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.path)
+    }
+}
+
+/// Parses the id with the `minecraft` namespace as default.
+impl FromStr for ResourceLocation {
+    type Err = ParseErrorKind;
+
+    fn from_str(string: &str) -> Result<Self, ParseErrorKind> {
+        Self::parse_with_namespace(string, "minecraft")
+    }
+}
+
+/// A set of known ids, like all the entity types. It is usually shared between commands in an `Arc`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Registry {
+    ids: BTreeSet<ResourceLocation>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, id: ResourceLocation) -> bool {
+        self.ids.insert(id)
+    }
+
+    pub fn contains(&self, id: &ResourceLocation) -> bool {
+        self.ids.contains(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ResourceLocation> {
+        self.ids.iter()
+    }
+}
+
+impl FromIterator<ResourceLocation> for Registry {
+    fn from_iter<I: IntoIterator<Item = ResourceLocation>>(iter: I) -> Self {
+        Self {
+            ids: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Parses a [`ResourceLocation`]. When it has a registry, only the ids in it are accepted and suggested.
+pub struct ResourceLocationParser {
+    default_namespace: String,
+    registry: Option<Arc<Registry>>,
+}
+
+impl ResourceLocationParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the namespace used when the input only has a path.
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        self.default_namespace = String::from(namespace);
+        self
+    }
+
+    pub fn registry(mut self, registry: Arc<Registry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+}
+
+impl Default for ResourceLocationParser {
+    fn default() -> Self {
+        Self {
+            default_namespace: String::from("minecraft"),
+            registry: None,
+        }
+    }
+}
+
+impl IterParser for ResourceLocationParser {
+    type Extract = (ResourceLocation,);
+    type ParserState = ();
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        _state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        let end = input.find(char::is_whitespace).unwrap_or(input.len());
+        if end == 0 {
+            return (
+                Err(ParseError::at_token(ParseErrorKind::EmptyInput, input).in_argument(0)),
+                None,
+            );
+        }
+
+        let id = ResourceLocation::parse_with_namespace(&input[..end], &self.default_namespace)
+            .and_then(|id| match &self.registry {
+                Some(registry) if !registry.contains(&id) => {
+                    Err(ParseErrorKind::UnknownResource(id.to_string()))
+                }
+                _ => Ok(id),
+            });
+
+        match id {
+            Ok(id) => (Ok(((id,), &input[end..])), None),
+            Err(kind) => (Err(ParseError::new(kind, 0..end).in_argument(0)), None),
+        }
+    }
+
+    fn regex(&self) -> String {
+        r"([a-z0-9_.\-]*:)?[a-z0-9_.\-/]+".into()
+    }
+
+    /// Suggests the ids of the registry starting with the input. Ids in the default namespace are also suggested
+    /// when only the start of their path is typed.
+    fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        let registry = match &self.registry {
+            Some(registry) => registry,
+            None => return Vec::new(),
+        };
+
+        registry
+            .iter()
+            .filter_map(|id| {
+                let full = id.to_string();
+                Suggestion::complete_word(input, &full).or_else(|| {
+                    if id.namespace() != self.default_namespace {
+                        return None;
+                    }
+                    Suggestion::complete_word(input, id.path())
+                        .map(|suggestion| Suggestion::new(suggestion.range, full.clone()))
+                })
+            })
+            .collect()
+    }
+
+    fn usage(&self) -> String {
+        String::from("<resource_location>")
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        vec![vec![NodeKind::argument(
+            &self.usage(),
+            ArgumentParser::ResourceLocation,
+        )]]
+    }
+}
+
+impl Argument for ResourceLocation {
+    type Parser = ResourceLocationParser;
+    type ParserState = ();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::builder::{literal, CommandBuilder};
+    use crate::command::{Command, CommandSpec};
+
+    #[test]
+    fn parse() {
+        let id: ResourceLocation = "minecraft:chicken".parse().unwrap();
+        assert_eq!((id.namespace(), id.path()), ("minecraft", "chicken"));
+        assert_eq!("chicken".parse(), Ok(id.clone()));
+        assert_eq!(":chicken".parse(), Ok(id));
+
+        let id: ResourceLocation = "my_pack:mobs/big.chicken-2".parse().unwrap();
+        assert_eq!(id.to_string(), "my_pack:mobs/big.chicken-2");
+
+        assert!("Minecraft:chicken".parse::<ResourceLocation>().is_err());
+        assert!("my/pack:chicken".parse::<ResourceLocation>().is_err());
+        assert!("a:b:c".parse::<ResourceLocation>().is_err());
+        assert!("minecraft:".parse::<ResourceLocation>().is_err());
+    }
+
+    #[test]
+    fn default_namespace() {
+        let command: CommandSpec<(), String, _, _, _> = literal("/summon")
+            .space()
+            .followed_by(ResourceLocationParser::new().namespace("mypack"))
+            .on_call(|id: ResourceLocation| move || id.to_string());
+
+        assert_eq!(
+            command.call((), "/summon chicken").unwrap(),
+            "mypack:chicken"
+        );
+        assert_eq!(
+            command.call((), "/summon minecraft:chicken").unwrap(),
+            "minecraft:chicken"
+        );
+
+        let err = command.call((), "/summon Chicken").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidResourceLocation);
+        assert_eq!(err.range, 8..15);
+    }
+
+    #[test]
+    fn registry() {
+        let registry: Registry = ["minecraft:chicken", "minecraft:cow", "mypack:chimera"]
+            .iter()
+            .map(|id| id.parse().unwrap())
+            .collect();
+        let command: CommandSpec<(), String, _, _, _> = literal("/summon")
+            .space()
+            .arg_registry(Arc::new(registry))
+            .on_call(|id: ResourceLocation| move || id.to_string());
+
+        assert_eq!(command.call((), "/summon cow").unwrap(), "minecraft:cow");
+        assert_eq!(
+            command.call((), "/summon mypack:chimera").unwrap(),
+            "mypack:chimera"
+        );
+
+        let err = command.call((), "/summon pig").unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::UnknownResource(String::from("minecraft:pig"))
+        );
+        assert_eq!(err.range, 8..11);
+
+        assert_eq!(
+            command.suggest("/summon ch", 10),
+            vec![Suggestion::new(8..10, String::from("minecraft:chicken"))]
+        );
+        assert_eq!(
+            command.suggest("/summon mypack:", 15),
+            vec![Suggestion::new(8..15, String::from("mypack:chimera"))]
+        );
+    }
+}
@@ -0,0 +1,615 @@
+use std::{fmt, str::FromStr};
+
+use indexmap::IndexMap;
+
+use super::{Argument, QuotedStringParser};
+use crate::graph::{ArgumentParser, NodeKind};
+use crate::parser::{IterParser, ParseError, ParseErrorKind};
+
+/// How deep compounds and lists can be nested by default, the same limit as the game.
+pub const MAX_SNBT_DEPTH: usize = 512;
+
+/// A stringified NBT value like `{CustomName:'"x"',Count:3b,Tags:[a,b]}`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Snbt {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    List(Vec<Snbt>),
+    /// The entries are kept in the order they were written. A repeated key overwrites the earlier value.
+    Compound(IndexMap<String, Snbt>),
+    ByteArray(Vec<i8>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Snbt {
+    /// Gets the value of a key, if this is a compound containing it.
+    pub fn get(&self, key: &str) -> Option<&Snbt> {
+        match self {
+            Snbt::Compound(entries) => entries.get(key),
+            _ => None,
+        }
+    }
+
+    /// Whether both values are of the same type, which is required for the elements of a list.
+    fn same_type(&self, other: &Snbt) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// The characters allowed in unquoted strings and numbers.
+fn is_unquoted(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_-.+".contains(c)
+}
+
+/// Parses an unquoted token like the game does: numbers with an optional type suffix, `true` and `false` as
+/// bytes, and anything else (including numbers out of range) as a string.
+fn typed_value(token: &str) -> Snbt {
+    fn integer(s: &str) -> bool {
+        let digits = s.strip_prefix(['+', '-']).unwrap_or(s);
+        digits == "0"
+            || (!digits.starts_with('0')
+                && !digits.is_empty()
+                && digits.bytes().all(|b| b.is_ascii_digit()))
+    }
+    fn float(s: &str) -> bool {
+        let s = s.strip_prefix(['+', '-']).unwrap_or(s);
+        let (mantissa, exponent) = match s.find(['e', 'E']) {
+            Some(i) => (&s[..i], Some(&s[i + 1..])),
+            None => (s, None),
+        };
+        let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        let exponent = exponent.map_or(true, |e| {
+            let e = e.strip_prefix(['+', '-']).unwrap_or(e);
+            !e.is_empty() && digits(e)
+        });
+        (!int.is_empty() || !frac.is_empty()) && digits(int) && digits(frac) && exponent
+    }
+
+    let (body, suffix) = match token.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&token[..i], c.to_ascii_lowercase()),
+        _ => (token, ' '),
+    };
+
+    let value = match suffix {
+        'b' if integer(body) => body.parse().ok().map(Snbt::Byte),
+        's' if integer(body) => body.parse().ok().map(Snbt::Short),
+        'l' if integer(body) => body.parse().ok().map(Snbt::Long),
+        'f' if float(body) => body.parse().ok().map(Snbt::Float),
+        'd' if float(body) => body.parse().ok().map(Snbt::Double),
+        ' ' if integer(body) => body.parse().ok().map(Snbt::Int),
+        // Without a suffix, the game only reads numbers with a `.` as doubles, so `01` and `1e5` are strings.
+        ' ' if float(body) && body.contains('.') => body.parse().ok().map(Snbt::Double),
+        _ => None,
+    };
+
+    value.unwrap_or_else(|| match token {
+        "true" => Snbt::Byte(1),
+        "false" => Snbt::Byte(0),
+        _ => Snbt::String(token.to_string()),
+    })
+}
+
+/// A cursor over the input that reads one value at a time.
+struct Reader<'a> {
+    input: &'a str,
+    pos: usize,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// The range of the next character, or an empty range at the end of the input.
+    fn next_range(&self) -> std::ops::Range<usize> {
+        self.pos..self.pos + self.peek().map_or(0, char::len_utf8)
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            Ok(())
+        } else {
+            Err(ParseError::new(
+                ParseErrorKind::ExpectedCharacter(c),
+                self.next_range(),
+            ))
+        }
+    }
+
+    /// Skips a `,` if there is one, returning whether another element can follow.
+    fn separator(&mut self) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(',') {
+            self.pos += 1;
+            self.skip_whitespace();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn unquoted(&mut self) -> &'a str {
+        let rest = &self.input[self.pos..];
+        let len = rest.find(|c| !is_unquoted(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// Reads a quoted string if the next character is a quote.
+    fn quoted(&mut self) -> Option<Result<String, ParseError>> {
+        let quote = self.peek().filter(|c| *c == '"' || *c == '\'')?;
+        let start = self.pos;
+        Some(
+            QuotedStringParser::parse_quoted(quote, &self.input[start..])
+                .map(|(string, len)| {
+                    self.pos += len;
+                    string
+                })
+                .map_err(|err| err.shift(start, 0)),
+        )
+    }
+
+    fn enter(&mut self) -> Result<(), ParseError> {
+        self.depth += 1;
+        if self.depth > self.max_depth {
+            return Err(ParseError::new(
+                ParseErrorKind::NestedTooDeep(self.max_depth),
+                self.next_range(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Snbt, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.compound(),
+            Some('[') => self.list(),
+            _ => {
+                if let Some(string) = self.quoted() {
+                    return string.map(Snbt::String);
+                }
+                let start = self.pos;
+                match self.unquoted() {
+                    "" => Err(ParseError::new(
+                        ParseErrorKind::ExpectedNbtValue,
+                        start..self.next_range().end,
+                    )),
+                    token => Ok(typed_value(token)),
+                }
+            }
+        }
+    }
+
+    fn compound(&mut self) -> Result<Snbt, ParseError> {
+        self.enter()?;
+        self.expect('{')?;
+        self.skip_whitespace();
+
+        let mut entries = IndexMap::new();
+        while self.peek() != Some('}') {
+            let start = self.pos;
+            let key = match self.quoted() {
+                Some(key) => key?,
+                None => self.unquoted().to_string(),
+            };
+            if key.is_empty() {
+                return Err(ParseError::new(
+                    ParseErrorKind::ExpectedNbtKey,
+                    start..self.next_range().end,
+                ));
+            }
+            self.expect(':')?;
+            entries.insert(key, self.value()?);
+
+            if !self.separator() {
+                break;
+            }
+        }
+
+        self.expect('}')?;
+        self.depth -= 1;
+        Ok(Snbt::Compound(entries))
+    }
+
+    fn list(&mut self) -> Result<Snbt, ParseError> {
+        self.enter()?;
+        self.expect('[')?;
+
+        // Typed arrays start with their type and a `;`, like `[I;1,2]`.
+        let mut chars = self.input[self.pos..].chars();
+        let array = match (chars.next(), chars.next() == Some(';')) {
+            (Some(kind), true) if kind.is_ascii_alphabetic() => Some(kind),
+            _ => None,
+        };
+        if let Some(kind) = array {
+            if !"BIL".contains(kind) {
+                return Err(ParseError::new(
+                    ParseErrorKind::InvalidNbtArray(kind),
+                    self.pos..self.pos + 1,
+                ));
+            }
+            self.pos += 2;
+        }
+
+        self.skip_whitespace();
+        let mut elements: Vec<Snbt> = Vec::new();
+        while self.peek() != Some(']') {
+            self.skip_whitespace();
+            let start = self.pos;
+            let element = self.value()?;
+
+            let valid = match (array, elements.first()) {
+                (Some('B'), _) => matches!(element, Snbt::Byte(_)),
+                (Some('I'), _) => matches!(element, Snbt::Int(_)),
+                (Some(_), _) => matches!(element, Snbt::Long(_)),
+                (None, Some(first)) => first.same_type(&element),
+                (None, None) => true,
+            };
+            if !valid {
+                return Err(ParseError::new(
+                    ParseErrorKind::MixedNbtList,
+                    start..self.pos,
+                ));
+            }
+            elements.push(element);
+
+            if !self.separator() {
+                break;
+            }
+        }
+
+        self.expect(']')?;
+        self.depth -= 1;
+
+        let elements = elements.into_iter();
+        Ok(match array {
+            Some('B') => Snbt::ByteArray(
+                elements
+                    .filter_map(|e| match e {
+                        Snbt::Byte(v) => Some(v),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            Some('I') => Snbt::IntArray(
+                elements
+                    .filter_map(|e| match e {
+                        Snbt::Int(v) => Some(v),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            Some(_) => Snbt::LongArray(
+                elements
+                    .filter_map(|e| match e {
+                        Snbt::Long(v) => Some(v),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            None => Snbt::List(elements.collect()),
+        })
+    }
+}
+
+/// Parses a whole string as a single value.
+impl FromStr for Snbt {
+    type Err = ParseError;
+
+    fn from_str(string: &str) -> Result<Self, ParseError> {
+        let mut reader = Reader {
+            input: string,
+            pos: 0,
+            depth: 0,
+            max_depth: MAX_SNBT_DEPTH,
+        };
+        let value = reader.value()?;
+        reader.skip_whitespace();
+        if reader.pos < string.len() {
+            return Err(ParseError::new(
+                ParseErrorKind::TooManyArguments,
+                reader.pos..string.len(),
+            ));
+        }
+        Ok(value)
+    }
+}
+
+/// Writes the value back as SNBT, quoting all strings and the keys that need it.
+impl fmt::Display for Snbt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn quoted(f: &mut fmt::Formatter<'_>, string: &str) -> fmt::Result {
+            let escaped = string.replace('\\', "\\\\").replace('"', "\\\"");
+            write!(f, "\"{}\"", escaped.replace('\n', "\\n"))
+        }
+        fn list<T: fmt::Display>(
+            f: &mut fmt::Formatter<'_>,
+            prefix: &str,
+            values: impl Iterator<Item = T>,
+        ) -> fmt::Result {
+            write!(f, "[{}", prefix)?;
+            for (i, value) in values.enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{}", value)?;
+            }
+            write!(f, "]")
+        }
+
+        match self {
+            Snbt::Byte(v) => write!(f, "{}b", v),
+            Snbt::Short(v) => write!(f, "{}s", v),
+            Snbt::Int(v) => write!(f, "{}", v),
+            Snbt::Long(v) => write!(f, "{}L", v),
+            Snbt::Float(v) => write!(f, "{:?}f", v),
+            Snbt::Double(v) => write!(f, "{:?}d", v),
+            Snbt::String(s) => quoted(f, s),
+            Snbt::List(values) => list(f, "", values.iter()),
+            Snbt::Compound(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    if !key.is_empty() && key.chars().all(is_unquoted) {
+                        write!(f, "{}", key)?;
+                    } else {
+                        quoted(f, key)?;
+                    }
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+            Snbt::ByteArray(values) => list(f, "B;", values.iter().map(|v| format!("{}B", v))),
+            Snbt::IntArray(values) => list(f, "I;", values.iter()),
+            Snbt::LongArray(values) => list(f, "L;", values.iter().map(|v| format!("{}L", v))),
+        }
+    }
+}
+
+/// Parses an [`Snbt`] value. The whole grammar can't be described by a regex, so `regex` only gives a superset
+/// and the exact grammar is checked while parsing.
+pub struct SnbtParser {
+    max_depth: usize,
+    compound_only: bool,
+}
+
+impl SnbtParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only accepts compounds, like the data of `/summon` and `/give`.
+    pub fn compound_only(mut self) -> Self {
+        self.compound_only = true;
+        self
+    }
+
+    /// Limits how deep compounds and lists can be nested.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+}
+
+impl Default for SnbtParser {
+    fn default() -> Self {
+        Self {
+            max_depth: MAX_SNBT_DEPTH,
+            compound_only: false,
+        }
+    }
+}
+
+impl IterParser for SnbtParser {
+    type Extract = (Snbt,);
+    type ParserState = ();
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        _state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        if input.is_empty() {
+            return (
+                Err(ParseError::at_token(ParseErrorKind::EmptyInput, input).in_argument(0)),
+                None,
+            );
+        }
+
+        let mut reader = Reader {
+            input,
+            pos: 0,
+            depth: 0,
+            max_depth: self.max_depth,
+        };
+        let value = if self.compound_only && reader.peek() != Some('{') {
+            Err(ParseError::new(
+                ParseErrorKind::ExpectedCharacter('{'),
+                reader.next_range(),
+            ))
+        } else {
+            reader.value()
+        };
+
+        match value {
+            Ok(value) => (Ok(((value,), &input[reader.pos..])), None),
+            Err(err) => (Err(err.in_argument(0)), None),
+        }
+    }
+
+    fn regex(&self) -> String {
+        let compound = r"\{.*\}";
+        if self.compound_only {
+            return compound.into();
+        }
+        format!(
+            r#"({}|\[.*\]|"([^"\\]|\\.)*"|'([^'\\]|\\.)*'|[0-9A-Za-z_\-.+]+)"#,
+            compound
+        )
+    }
+
+    fn usage(&self) -> String {
+        String::from("<snbt>")
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        let parser = if self.compound_only {
+            ArgumentParser::NbtCompoundTag
+        } else {
+            ArgumentParser::NbtTag
+        };
+        vec![vec![NodeKind::argument(&self.usage(), parser)]]
+    }
+}
+
+impl Argument for Snbt {
+    type Parser = SnbtParser;
+    type ParserState = ();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::builder::{literal, CommandBuilder};
+    use crate::command::{Command, CommandSpec};
+    use crate::regex::NFA;
+
+    #[test]
+    fn values() {
+        let cases = [
+            ("3b", Snbt::Byte(3)),
+            ("-3S", Snbt::Short(-3)),
+            ("3", Snbt::Int(3)),
+            ("3L", Snbt::Long(3)),
+            ("1.5f", Snbt::Float(1.5)),
+            ("1d", Snbt::Double(1.0)),
+            ("1.5", Snbt::Double(1.5)),
+            (".5e2", Snbt::Double(50.0)),
+            ("true", Snbt::Byte(1)),
+            ("300b", Snbt::String(String::from("300b"))),
+            ("01", Snbt::String(String::from("01"))),
+            ("1e5", Snbt::String(String::from("1e5"))),
+            ("1.e5", Snbt::Double(100000.0)),
+            ("1e5d", Snbt::Double(100000.0)),
+            ("abc", Snbt::String(String::from("abc"))),
+            ("'a \"b\"'", Snbt::String(String::from("a \"b\""))),
+        ];
+        for (input, value) in cases.iter() {
+            assert_eq!(&input.parse::<Snbt>().unwrap(), value, "{}", input);
+        }
+    }
+
+    #[test]
+    fn nested() {
+        let value: Snbt = r#"{CustomName:'"x"',Count:3b, Tags : [a,b,], Pos:[I;1,2], "a b":{}}"#
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            value.get("CustomName"),
+            Some(&Snbt::String(String::from("\"x\"")))
+        );
+        assert_eq!(value.get("Count"), Some(&Snbt::Byte(3)));
+        assert_eq!(
+            value.get("Tags"),
+            Some(&Snbt::List(vec![
+                Snbt::String(String::from("a")),
+                Snbt::String(String::from("b"))
+            ]))
+        );
+        assert_eq!(value.get("Pos"), Some(&Snbt::IntArray(vec![1, 2])));
+        assert_eq!(
+            value.to_string(),
+            r#"{CustomName:"\"x\"",Count:3b,Tags:["a","b"],Pos:[I;1,2],"a b":{}}"#
+        );
+        assert_eq!(value.to_string().parse::<Snbt>().unwrap(), value);
+
+        assert_eq!(
+            "[B;1b,2b]".parse::<Snbt>().unwrap(),
+            Snbt::ByteArray(vec![1, 2])
+        );
+        assert_eq!("[L;1L]".parse::<Snbt>().unwrap().to_string(), "[L;1L]");
+    }
+
+    #[test]
+    fn errors() {
+        let err = |input: &str| input.parse::<Snbt>().unwrap_err();
+
+        assert_eq!(err("[1,a]").kind, ParseErrorKind::MixedNbtList);
+        assert_eq!(err("[1,a]").range, 3..4);
+        assert_eq!(err("[I;1,2b]").range, 5..7);
+        assert_eq!(err("[X;1]").kind, ParseErrorKind::InvalidNbtArray('X'));
+        assert_eq!(err("{:1}").kind, ParseErrorKind::ExpectedNbtKey);
+        assert_eq!(err("{a 1}").kind, ParseErrorKind::ExpectedCharacter(':'));
+        assert_eq!(err("{a 1}").range, 3..4);
+        assert_eq!(err("{a:}").kind, ParseErrorKind::ExpectedNbtValue);
+        assert_eq!(err("{a:}").range, 3..4);
+        assert_eq!(err("{a:1").kind, ParseErrorKind::ExpectedCharacter('}'));
+        assert_eq!(err("{a:1").range, 4..4);
+        assert_eq!(err("{a:'x\\q'}").kind, ParseErrorKind::InvalidEscape('q'));
+        assert_eq!(err("{a:'x\\q'}").range, 5..7);
+
+        let deep = format!(
+            "{}{}",
+            "[".repeat(MAX_SNBT_DEPTH + 1),
+            "]".repeat(MAX_SNBT_DEPTH + 1)
+        );
+        assert_eq!(
+            err(&deep).kind,
+            ParseErrorKind::NestedTooDeep(MAX_SNBT_DEPTH)
+        );
+        assert_eq!(err(&deep).range, MAX_SNBT_DEPTH..MAX_SNBT_DEPTH + 1);
+    }
+
+    #[test]
+    fn argument() {
+        let command: CommandSpec<(), Option<Snbt>, _, _, _> = literal("/summon")
+            .space()
+            .arg::<String>()
+            .space()
+            .followed_by(SnbtParser::new().compound_only().max_depth(2))
+            .space()
+            .arg::<u32>()
+            .on_call(|_entity: String, data: Snbt, _count: u32| move || data.get("Name").cloned());
+
+        assert_eq!(
+            command
+                .call((), "/summon pig {Name: 'Mr Pig', Tags: [a]} 3")
+                .unwrap(),
+            Some(Snbt::String(String::from("Mr Pig")))
+        );
+
+        let err = command.call((), "/summon pig 3b 3").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::ExpectedCharacter('{'));
+        assert_eq!(err.range, 12..13);
+
+        let err = command.call((), "/summon pig {a:[[1]]} 3").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::NestedTooDeep(2));
+        assert_eq!(err.range, 16..17);
+
+        assert!(NFA::<usize>::regex(&SnbtParser::new().regex()).is_ok());
+    }
+}
@@ -1,4 +1,6 @@
 use crate::argument::Argument;
+use crate::graph::{ArgumentParser, NodeKind, StringKind};
+use crate::parser::{ParseError, ParseErrorKind};
 
 #[derive(Debug, Clone)]
 pub struct StringWildcard {
@@ -32,13 +34,16 @@ impl crate::parser::IterParser for StringWildcardParser {
         _state: Self::ParserState,
         input: &'p str,
     ) -> (
-        anyhow::Result<((StringWildcard,), &'p str)>,
+        Result<((StringWildcard,), &'p str), ParseError>,
         Option<Self::ParserState>,
     ) {
         let string = input.trim();
 
         if string.is_empty() {
-            return (Err(anyhow::anyhow!("Empty input")), None);
+            return (
+                Err(ParseError::at_token(ParseErrorKind::EmptyInput, input).in_argument(0)),
+                None,
+            );
         }
         (
             Ok((
@@ -54,6 +59,17 @@ impl crate::parser::IterParser for StringWildcardParser {
     fn regex(&self) -> String {
         ".*".into()
     }
+
+    fn usage(&self) -> String {
+        String::from("<string...>")
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        vec![vec![NodeKind::argument(
+            &self.usage(),
+            ArgumentParser::String(StringKind::GreedyPhrase),
+        )]]
+    }
 }
 
 impl Argument for StringWildcard {
@@ -1,5 +1,6 @@
 use super::Argument;
-use crate::parser::IterParser;
+use crate::graph::{ArgumentParser, NodeKind, StringKind};
+use crate::parser::{IterParser, ParseError, ParseErrorKind};
 
 #[derive(Default)]
 pub struct StringParser {}
@@ -15,13 +16,16 @@ impl IterParser for StringParser {
         _state: Self::ParserState,
         input: &'p str,
     ) -> (
-        anyhow::Result<(Self::Extract, &'p str)>,
+        Result<(Self::Extract, &'p str), ParseError>,
         Option<Self::ParserState>,
     ) {
         let string = input.trim();
 
         if string.is_empty() {
-            return (Err(anyhow::anyhow!("Empty input")), None);
+            return (
+                Err(ParseError::at_token(ParseErrorKind::EmptyInput, input).in_argument(0)),
+                None,
+            );
         }
 
         let pos = string.find(' ').unwrap_or(string.len());
@@ -35,6 +39,17 @@ impl IterParser for StringParser {
     fn regex(&self) -> String {
         "\\S+".into()
     }
+
+    fn usage(&self) -> String {
+        String::from("<string>")
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        vec![vec![NodeKind::argument(
+            &self.usage(),
+            ArgumentParser::String(StringKind::SingleWord),
+        )]]
+    }
 }
 
 impl Argument for String {
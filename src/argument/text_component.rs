@@ -0,0 +1,295 @@
+use std::convert::TryFrom;
+
+use super::{Argument, Json, JsonParser, JSON_REGEX};
+use crate::graph::{ArgumentParser, NodeKind};
+use crate::parser::{IterParser, ParseError, ParseErrorKind};
+
+/// What a [`TextComponent`] displays.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextContent {
+    Text(String),
+    /// A translation key with the components filling in its `%s` placeholders.
+    Translate {
+        key: String,
+        with: Vec<TextComponent>,
+    },
+    Score {
+        name: String,
+        objective: String,
+    },
+    Selector(String),
+    Keybind(String),
+}
+
+/// A chat component like `{"text":"Hi","color":"red","extra":["!"]}`. The style fields are `None` when they are
+/// inherited from the parent component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextComponent {
+    pub content: TextContent,
+    pub color: Option<String>,
+    pub font: Option<String>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underlined: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub obfuscated: Option<bool>,
+    pub insertion: Option<String>,
+    /// The `clickEvent` and `hoverEvent` are kept as they were written.
+    pub click_event: Option<Json>,
+    pub hover_event: Option<Json>,
+    pub extra: Vec<TextComponent>,
+}
+
+impl TextComponent {
+    pub fn text(text: &str) -> Self {
+        Self {
+            content: TextContent::Text(String::from(text)),
+            color: None,
+            font: None,
+            bold: None,
+            italic: None,
+            underlined: None,
+            strikethrough: None,
+            obfuscated: None,
+            insertion: None,
+            click_event: None,
+            hover_event: None,
+            extra: Vec::new(),
+        }
+    }
+
+    /// The text of the component and its children, without any styling. Translations, scores, selectors and
+    /// keybinds are shown as their key.
+    pub fn plain(&self) -> String {
+        let mut text = match &self.content {
+            TextContent::Text(text) => text.clone(),
+            TextContent::Translate { key, .. } => key.clone(),
+            TextContent::Score { name, .. } => name.clone(),
+            TextContent::Selector(selector) => selector.clone(),
+            TextContent::Keybind(key) => key.clone(),
+        };
+        self.extra
+            .iter()
+            .for_each(|extra| text.push_str(&extra.plain()));
+        text
+    }
+}
+
+/// Converts the JSON like the game does: a string is a text component, an array is its first element with the
+/// others appended to `extra`, and an object needs one of `text`, `translate`, `score`, `selector` or `keybind`.
+/// The error describes the part that is wrong.
+impl TryFrom<&Json> for TextComponent {
+    type Error = String;
+
+    fn try_from(json: &Json) -> Result<Self, String> {
+        let object = match json {
+            Json::String(text) => return Ok(TextComponent::text(text)),
+            Json::Number(number) => return Ok(TextComponent::text(&number.to_string())),
+            Json::Bool(value) => return Ok(TextComponent::text(&value.to_string())),
+            Json::Array(elements) => {
+                let mut elements = elements.iter().map(TextComponent::try_from);
+                let mut first = elements
+                    .next()
+                    .ok_or_else(|| String::from("an empty array is not a component"))??;
+                for element in elements {
+                    first.extra.push(element?);
+                }
+                return Ok(first);
+            }
+            Json::Null => return Err(String::from("null is not a component")),
+            Json::Object(_) => json,
+        };
+
+        let string = |key: &str| match object.get(key) {
+            None => Ok(None),
+            Some(Json::String(value)) => Ok(Some(value.clone())),
+            Some(_) => Err(format!("'{}' must be a string", key)),
+        };
+        let flag = |key: &str| match object.get(key) {
+            None => Ok(None),
+            Some(Json::Bool(value)) => Ok(Some(*value)),
+            Some(_) => Err(format!("'{}' must be a boolean", key)),
+        };
+        let components = |key: &str| match object.get(key) {
+            None => Ok(Vec::new()),
+            Some(Json::Array(elements)) if !elements.is_empty() => {
+                elements.iter().map(TextComponent::try_from).collect()
+            }
+            Some(_) => Err(format!("'{}' must be a non-empty array", key)),
+        };
+
+        let content = if let Some(text) = string("text")? {
+            TextContent::Text(text)
+        } else if let Some(key) = string("translate")? {
+            TextContent::Translate {
+                key,
+                with: components("with")?,
+            }
+        } else if let Some(score) = object.get("score") {
+            let field = |key: &str| {
+                score
+                    .get(key)
+                    .and_then(Json::as_str)
+                    .map(String::from)
+                    .ok_or_else(|| format!("'score' needs a string '{}'", key))
+            };
+            TextContent::Score {
+                name: field("name")?,
+                objective: field("objective")?,
+            }
+        } else if let Some(selector) = string("selector")? {
+            TextContent::Selector(selector)
+        } else if let Some(key) = string("keybind")? {
+            TextContent::Keybind(key)
+        } else {
+            return Err(String::from(
+                "expected 'text', 'translate', 'score', 'selector' or 'keybind'",
+            ));
+        };
+
+        Ok(TextComponent {
+            content,
+            color: string("color")?,
+            font: string("font")?,
+            bold: flag("bold")?,
+            italic: flag("italic")?,
+            underlined: flag("underlined")?,
+            strikethrough: flag("strikethrough")?,
+            obfuscated: flag("obfuscated")?,
+            insertion: string("insertion")?,
+            click_event: object.get("clickEvent").cloned(),
+            hover_event: object.get("hoverEvent").cloned(),
+            extra: components("extra")?,
+        })
+    }
+}
+
+/// Parses a JSON value and converts it to a [`TextComponent`]. Invalid components are reported on the whole
+/// value.
+#[derive(Default)]
+pub struct TextComponentParser;
+
+impl IterParser for TextComponentParser {
+    type Extract = (TextComponent,);
+    type ParserState = ();
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        _state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        let ((json,), rest) = match JsonParser.parse((), input) {
+            (Ok(parsed), _) => parsed,
+            (Err(err), _) => return (Err(err), None),
+        };
+
+        match TextComponent::try_from(json.get()) {
+            Ok(component) => (Ok(((component,), rest)), None),
+            Err(message) => (
+                Err(ParseError::new(
+                    ParseErrorKind::InvalidTextComponent(message),
+                    0..input.len() - rest.len(),
+                )
+                .in_argument(0)),
+                None,
+            ),
+        }
+    }
+
+    fn regex(&self) -> String {
+        JSON_REGEX.into()
+    }
+
+    fn usage(&self) -> String {
+        String::from("<component>")
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        vec![vec![NodeKind::argument(
+            &self.usage(),
+            ArgumentParser::Component,
+        )]]
+    }
+}
+
+impl Argument for TextComponent {
+    type Parser = TextComponentParser;
+    type ParserState = ();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::argument::EntitySelector;
+    use crate::command::builder::{literal, CommandBuilder};
+    use crate::command::{Command, CommandSpec};
+
+    fn component(json: &str) -> Result<TextComponent, String> {
+        TextComponent::try_from(&json.parse::<Json>().unwrap())
+    }
+
+    #[test]
+    fn convert() {
+        let hi = component(r#"["Hi ", {"selector": "@p", "bold": true}, "!"]"#).unwrap();
+        assert_eq!(hi.plain(), "Hi @p!");
+        assert_eq!(hi.extra[0].bold, Some(true));
+        assert_eq!(
+            hi.extra[0].content,
+            TextContent::Selector(String::from("@p"))
+        );
+
+        let translated =
+            component(r#"{"translate": "chat.type.text", "with": ["a", "b"], "color": "red"}"#)
+                .unwrap();
+        assert_eq!(translated.color.as_deref(), Some("red"));
+        match translated.content {
+            TextContent::Translate { key, with } => {
+                assert_eq!(key, "chat.type.text");
+                assert_eq!(
+                    with,
+                    vec![TextComponent::text("a"), TextComponent::text("b")]
+                );
+            }
+            content => panic!("unexpected content {:?}", content),
+        }
+
+        assert!(component(r#"{"bold": true}"#).is_err());
+        assert!(component(r#"{"text": "a", "bold": "yes"}"#).is_err());
+        assert!(component(r#"{"score": {"name": "@s"}}"#).is_err());
+        assert!(component("[]").is_err());
+    }
+
+    #[test]
+    fn argument() {
+        let command: CommandSpec<(), String, _, _, _> = literal("/tellraw")
+            .space()
+            .arg::<EntitySelector>()
+            .space()
+            .arg::<TextComponent>()
+            .on_call(|_targets: EntitySelector, message: TextComponent| move || message.plain());
+
+        assert_eq!(
+            command
+                .call((), r#"/tellraw @a {"text": "Hello", "extra": [" world"]}"#)
+                .unwrap(),
+            "Hello world"
+        );
+
+        let err = command
+            .call((), r#"/tellraw @a {"color": "red"}"#)
+            .unwrap_err();
+        assert!(matches!(err.kind, ParseErrorKind::InvalidTextComponent(_)));
+        assert_eq!(err.range, 12..28);
+        assert_eq!(err.argument, Some(1));
+
+        let err = command
+            .call((), r#"/tellraw @a {"text": "a" "#)
+            .unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::ExpectedCharacter('}'));
+        assert_eq!(err.range, 25..25);
+    }
+}
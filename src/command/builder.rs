@@ -1,11 +1,16 @@
-use super::CommandSpec;
-use crate::argument::ChoiceParser;
+use super::{redirect::RedirectSpec, CommandSpec};
+use crate::argument::{ChoiceParser, Integer, IntegerParser, Registry, ResourceLocationParser};
 use crate::parser::Opt;
 use crate::{
     argument::Argument,
     generic::Func,
-    parser::{self, And, IterParser, MaybeSpaces, OneOrMoreSpace},
+    parser::{
+        self, And, FlagSet, IterParser, Map, MaybeSpaces, Named, OneOrMoreSpace, Or, Repeat,
+        Require, WithFlags,
+    },
 };
+use regex::Regex;
+use std::{ops::RangeBounds, sync::Arc};
 
 // use std::marker::PhantomData;
 pub fn space() -> OneOrMoreSpace {
@@ -13,21 +18,59 @@ pub fn space() -> OneOrMoreSpace {
 }
 
 pub fn literal(value: &str) -> parser::Literal {
-    parser::Literal {
-        value: String::from(value),
-    }
+    parser::Literal::new(String::from(value))
 }
 
 pub trait CommandBuilder {
     type Parser: IterParser;
     fn arg<A: Argument>(self) -> And<Self::Parser, <A as Argument>::Parser>;
     fn opt_arg<A: Argument>(self) -> And<Self::Parser, Opt<A::Parser>>;
+    /// Adds an argument with a name, which is used in the usage, errors and suggestions of the command instead
+    /// of the type. A description and example values can be added by calling `.describe(...)` and `.examples(...)`
+    /// right after. The argument is passed to the closure exactly like with `arg`.
+    fn arg_named<A: Argument>(self, name: &str) -> And<Self::Parser, Named<A::Parser>>;
+    /// The named version of `opt_arg`.
+    fn opt_arg_named<A: Argument>(self, name: &str) -> And<Self::Parser, Opt<Named<A::Parser>>>;
+    /// Adds an integer argument that only accepts values in the range, like `arg_range::<i32>(-64..=320)`.
+    /// Panics if the range is empty.
+    fn arg_range<I: Integer>(
+        self,
+        range: impl RangeBounds<I>,
+    ) -> And<Self::Parser, IntegerParser<I>>;
+    /// Adds a [`ResourceLocation`](crate::argument::ResourceLocation) argument that only accepts and suggests
+    /// the ids in the registry.
+    fn arg_registry(self, registry: Arc<Registry>) -> And<Self::Parser, ResourceLocationParser>;
+    /// Adds an argument that is repeated zero or more times, separated by spaces, and passed as a `Vec`.
+    /// Use [`Repeat`] with `followed_by` for other counts or separators.
+    fn many<A: Argument + Clone>(self) -> And<Self::Parser, Repeat<A::Parser>>;
+    /// Like `many`, but needs at least one value.
+    fn many1<A: Argument + Clone>(self) -> And<Self::Parser, Repeat<A::Parser>>;
     fn space(self) -> And<Self::Parser, OneOrMoreSpace>;
     fn opt_space(self) -> And<Self::Parser, MaybeSpaces>;
     fn choice(self, args: Vec<String>) -> And<Self::Parser, ChoiceParser>;
     fn opt_choice(self, args: Vec<String>) -> And<Self::Parser, Opt<ChoiceParser>>;
     fn literal(self, literal: &str) -> And<Self::Parser, parser::Literal>;
     fn followed_by<P: IterParser>(self, parser: P) -> And<Self::Parser, P>;
+    /// Makes the parser so far an alternative to the given parser. The extract is an [`Either`](parser::Either)
+    /// telling which of the two matched. Chain it to get more alternatives.
+    fn or<P: IterParser>(self, parser: P) -> Or<Self::Parser, P>;
+    /// Forks the command after the parser so far, continuing with either `a` or `b`.
+    /// For example `literal("/team").space().branch(literal("add").space().arg::<String>(), literal("list"))`.
+    fn branch<A: IterParser, B: IterParser>(self, a: A, b: B) -> And<Self::Parser, Or<A, B>>;
+    /// Turns the arguments of the parser so far into a single value. This is useful for giving every branch
+    /// the same extract type, like a variant of an enum.
+    fn map<F: Func<<Self::Parser as IterParser>::Extract>>(self, f: F) -> Map<Self::Parser, F>;
+    /// Only lets the source use the parser so far, and everything after it, when the predicate holds. See
+    /// [`Source`](parser::Source).
+    fn requires<S: 'static>(
+        self,
+        predicate: impl Fn(&S) -> bool + 'static,
+    ) -> Require<Self::Parser>;
+    /// Accepts the flags anywhere among the arguments of the parser so far, adding their
+    /// [`FlagValues`](parser::FlagValues) as the last argument. Call it right before `on_call`.
+    fn flags(self, flags: FlagSet) -> WithFlags<Self::Parser>;
+    /// Finishes the command, calling `f` with the arguments when it is parsed.
+    /// Panics if the parsers of the command give an invalid regex, see `try_on_call`.
     fn on_call<GameState, CommandResult, F1, F2>(
         self,
         f: F1,
@@ -35,6 +78,29 @@ pub trait CommandBuilder {
     where
         F1: Func<<Self::Parser as IterParser>::Extract, Output = F2>,
         F2: Func<GameState, Output = CommandResult>;
+    /// Finishes the start of a command that continues with any command of the dispatcher it is registered in,
+    /// after a space. `f` is called with the arguments, and returns how to change the game state for that
+    /// command. See [`Dispatcher::register_redirect`](super::dispatcher::Dispatcher::register_redirect).
+    fn redirect<GameState, F1, F2>(
+        self,
+        f: F1,
+    ) -> RedirectSpec<GameState, F1, F2, And<Self::Parser, OneOrMoreSpace>>
+    where
+        F1: Func<<Self::Parser as IterParser>::Extract, Output = F2>,
+        F2: Func<GameState, Output = GameState>;
+    /// Like `on_call`, but returns an error if the regex of the command can't be compiled. This can only
+    /// happen when a parser from outside of this crate returns an invalid regex.
+    #[allow(clippy::type_complexity)]
+    fn try_on_call<GameState, CommandResult, F1, F2>(
+        self,
+        f: F1,
+    ) -> Result<
+        CommandSpec<GameState, CommandResult, F1, F2, And<Self::Parser, MaybeSpaces>>,
+        regex::Error,
+    >
+    where
+        F1: Func<<Self::Parser as IterParser>::Extract, Output = F2>,
+        F2: Func<GameState, Output = CommandResult>;
 }
 
 impl<T> CommandBuilder for T
@@ -59,6 +125,35 @@ where
         }
     }
 
+    fn arg_named<A: Argument>(self, name: &str) -> And<Self::Parser, Named<A::Parser>> {
+        self.followed_by(Named::new(A::Parser::default(), name))
+    }
+
+    fn opt_arg_named<A: Argument>(self, name: &str) -> And<Self::Parser, Opt<Named<A::Parser>>> {
+        self.followed_by(Opt {
+            parser: Named::new(A::Parser::default(), name),
+        })
+    }
+
+    fn arg_range<I: Integer>(
+        self,
+        range: impl RangeBounds<I>,
+    ) -> And<Self::Parser, IntegerParser<I>> {
+        self.followed_by(IntegerParser::new(range))
+    }
+
+    fn arg_registry(self, registry: Arc<Registry>) -> And<Self::Parser, ResourceLocationParser> {
+        self.followed_by(ResourceLocationParser::new().registry(registry))
+    }
+
+    fn many<A: Argument + Clone>(self) -> And<Self::Parser, Repeat<A::Parser>> {
+        self.followed_by(Repeat::new(A::Parser::default()))
+    }
+
+    fn many1<A: Argument + Clone>(self) -> And<Self::Parser, Repeat<A::Parser>> {
+        self.followed_by(Repeat::new(A::Parser::default()).min(1))
+    }
+
     fn space(self) -> And<Self::Parser, OneOrMoreSpace> {
         self.followed_by(space())
     }
@@ -91,6 +186,35 @@ where
         And { a: self, b: other }
     }
 
+    fn or<P: IterParser>(self, other: P) -> Or<Self::Parser, P> {
+        Or { a: self, b: other }
+    }
+
+    fn branch<A: IterParser, B: IterParser>(self, a: A, b: B) -> And<Self::Parser, Or<A, B>> {
+        self.followed_by(a.or(b))
+    }
+
+    fn map<F: Func<<Self::Parser as IterParser>::Extract>>(self, f: F) -> Map<Self::Parser, F> {
+        Map {
+            parser: self,
+            map: f,
+        }
+    }
+
+    fn requires<S: 'static>(
+        self,
+        predicate: impl Fn(&S) -> bool + 'static,
+    ) -> Require<Self::Parser> {
+        Require::new(self, predicate)
+    }
+
+    fn flags(self, flags: FlagSet) -> WithFlags<Self::Parser> {
+        WithFlags {
+            parser: self,
+            flags,
+        }
+    }
+
     fn on_call<GameState, CommandResult, F1, F2>(
         self,
         f: F1,
@@ -99,19 +223,64 @@ where
         F1: Func<<Self::Parser as IterParser>::Extract, Output = F2>,
         F2: Func<GameState, Output = CommandResult>,
     {
-        CommandSpec {
+        self.try_on_call(f)
+            .expect("The parsers of the command returned an invalid regex")
+    }
+
+    fn redirect<GameState, F1, F2>(
+        self,
+        f: F1,
+    ) -> RedirectSpec<GameState, F1, F2, And<Self::Parser, OneOrMoreSpace>>
+    where
+        F1: Func<<Self::Parser as IterParser>::Extract, Output = F2>,
+        F2: Func<GameState, Output = GameState>,
+    {
+        RedirectSpec {
+            parser: self.space(),
+            modifier: f,
+            description: None,
+            gamestate: Default::default(),
+            modifier_result: Default::default(),
+        }
+    }
+
+    fn try_on_call<GameState, CommandResult, F1, F2>(
+        self,
+        f: F1,
+    ) -> Result<
+        CommandSpec<GameState, CommandResult, F1, F2, And<Self::Parser, MaybeSpaces>>,
+        regex::Error,
+    >
+    where
+        F1: Func<<Self::Parser as IterParser>::Extract, Output = F2>,
+        F2: Func<GameState, Output = CommandResult>,
+    {
+        // Compiled once here, so calling the command only has to run the matcher. This is the regex of the
+        // parser below, which we can't call since `And` only implements `IterParser` with extra bounds.
+        // Parsers don't stop at a newline, so neither does `.` here.
+        let regex = Regex::new(&format!(
+            "(?s)^(?:({})({}))$",
+            self.regex(),
+            MaybeSpaces {}.regex()
+        ))?;
+
+        Ok(CommandSpec {
             parser: self.followed_by(MaybeSpaces {}),
             mapping: f,
+            regex,
+            description: None,
             gamestate: Default::default(),
             command_result: Default::default(),
             mapping_result: Default::default(),
-        }
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::command::{Command, CommandSpec};
+    use crate::argument::StringWildcard;
+    use crate::command::{Command, CommandSpec, Handler};
+    use crate::parser::{Either, IterParser, ParseError, ParseErrorKind, Suggestion};
 
     use super::{literal, CommandBuilder};
 
@@ -216,4 +385,305 @@ mod tests {
         assert!(cmd.call((x, y), "/echo 10").is_ok());
         println!("{:?}", cmd.call((x, y), "/echo 10 "));
     }
+
+    #[test]
+    fn error_spans() {
+        let command: CommandSpec<(usize,), usize, _, _, _> = literal("/tp")
+            .space()
+            .arg::<u32>()
+            .space()
+            .opt_arg::<u32>()
+            .space()
+            .arg::<bool>()
+            .on_call(|x: u32, _y: Option<(u32,)>, _z: bool| move |_: usize| x as usize);
+
+        let err = command.call((0,), "/tq 1 2 true").unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::ExpectedLiteral(String::from("/tp"))
+        );
+        assert_eq!(err.range, 0..3);
+        assert_eq!(err.argument, None);
+
+        let err = command.call((0,), "/tp abc 2 true").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidNumber);
+        assert_eq!(err.range, 4..7);
+        assert_eq!(err.argument, Some(0));
+
+        // Skipping the optional argument fails earlier, so the error from the boolean is reported.
+        let err = command.call((0,), "/tp 1 2 maybe").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidBoolean);
+        assert_eq!(err.range, 8..13);
+        assert_eq!(err.argument, Some(2));
+
+        let err = command.call((0,), "/tp 1 2 true false").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::TooManyArguments);
+        assert_eq!(err.range, 13..18);
+
+        let err = command.call((0,), "/tp 99999999999 2 true").unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::OutOfRange {
+                min: String::from("0"),
+                max: String::from("4294967295")
+            }
+        );
+        assert_eq!(err.range, 4..15);
+    }
+
+    #[test]
+    fn usage() {
+        let command: CommandSpec<(usize,), usize, _, _, _> = literal("/tp")
+            .space()
+            .arg::<u32>()
+            .space()
+            .arg_named::<f32>("y")
+            .describe("The height to teleport to")
+            .opt_space()
+            .opt_arg::<String>()
+            .on_call(|_: u32, _: f32, _: Option<(String,)>| move |_: usize| 0)
+            .describe("Teleports you");
+
+        assert_eq!(command.usage(), "/tp <u32> <y> [<string>]");
+        assert_eq!(
+            command.help(),
+            "/tp <u32> <y> [<string>] - Teleports you\n    <y>: The height to teleport to"
+        );
+
+        let command: CommandSpec<(usize,), usize, _, _, _> = literal("/gamemode")
+            .space()
+            .choice(vec!["creative".into(), "survival".into()])
+            .on_call(|_: String| move |_: usize| 0);
+
+        assert_eq!(command.usage(), "/gamemode (creative|survival)");
+        assert_eq!(command.help(), "/gamemode (creative|survival)");
+    }
+
+    #[test]
+    fn named_arguments() {
+        let command: CommandSpec<(usize,), usize, _, _, _> = literal("/fill")
+            .space()
+            .arg_named::<u32>("radius")
+            .describe("How far to fill")
+            .examples(["5", "10"])
+            .opt_space()
+            .opt_arg_named::<bool>("hollow")
+            .on_call(|radius: u32, hollow: Option<(bool,)>| {
+                move |_: usize| match hollow {
+                    Some((true,)) => 0,
+                    _ => radius as usize,
+                }
+            });
+
+        assert_eq!(command.call((0,), "/fill 5").unwrap(), 5);
+        assert_eq!(command.call((0,), "/fill 5 true").unwrap(), 0);
+        assert_eq!(command.usage(), "/fill <radius> [<hollow>]");
+
+        let err = command.call((0,), "/fill big").unwrap_err();
+        assert_eq!(err.argument, Some(0));
+        assert_eq!(err.argument_name.as_deref(), Some("radius"));
+        assert_eq!(err.to_string(), "Not a number at 6..9 (argument <radius>)");
+
+        let err = command.call((0,), "/fill 5 maybe").unwrap_err();
+        assert_eq!(err.argument, Some(1));
+        assert_eq!(err.argument_name.as_deref(), Some("hollow"));
+
+        assert_eq!(
+            command.suggest("/fill 1", 7),
+            vec![
+                Suggestion::new(6..7, String::from("10")).with_tooltip("<radius>: How far to fill")
+            ]
+        );
+        assert_eq!(
+            command.suggest("/fill 5 t", 9),
+            vec![Suggestion::new(8..9, String::from("true")).with_tooltip("<hollow>")]
+        );
+    }
+
+    #[test]
+    fn branches() {
+        // Extracts are cloned while backtracking.
+        #[derive(Debug, Clone, PartialEq)]
+        enum Team {
+            Add(String),
+            Remove(String),
+            List,
+        }
+        type Branches = Either<(Team,), (Either<(Team,), (Team,)>,)>;
+
+        let command: CommandSpec<(), Team, _, _, _> = literal("/team")
+            .space()
+            .branch(
+                literal("add").space().arg::<String>().map(Team::Add),
+                literal("remove")
+                    .space()
+                    .arg::<String>()
+                    .map(Team::Remove)
+                    .or(literal("list").map(|| Team::List)),
+            )
+            .on_call(|team: Branches| {
+                let (team,) = match team {
+                    Either::Left(add) => add,
+                    Either::Right((other,)) => other.into_inner(),
+                };
+                move || team.clone()
+            });
+
+        assert_eq!(
+            command.call((), "/team add red").unwrap(),
+            Team::Add(String::from("red"))
+        );
+        assert_eq!(
+            command.call((), "/team remove red").unwrap(),
+            Team::Remove(String::from("red"))
+        );
+        assert_eq!(command.call((), "/team list").unwrap(), Team::List);
+        assert_eq!(
+            command.usage(),
+            "/team (add <string>|(remove <string>|list))"
+        );
+
+        let err = command.call((), "/team join red").unwrap_err();
+        assert_eq!(err.range, 6..10);
+        assert!(command.call((), "/team list red").is_err());
+
+        assert_eq!(
+            command.suggest("/team ", 6),
+            vec![
+                Suggestion::new(6..6, String::from("add")),
+                Suggestion::new(6..6, String::from("remove")),
+                Suggestion::new(6..6, String::from("list")),
+            ]
+        );
+    }
+
+    #[test]
+    fn backtracks_after_input_is_left() {
+        // The first way to parse "ab" takes "a" and leaves "b", only skipping the first choice parses it all.
+        let command = literal("/x")
+            .space()
+            .opt_choice(vec![String::from("a")])
+            .opt_choice(vec![String::from("ab")])
+            .on_call(|a: Option<(String,)>, ab: Option<(String,)>| {
+                move |_: usize| (a.is_some(), ab.is_some())
+            });
+        assert_eq!(command.call((0,), "/x ab").unwrap(), (false, true));
+        assert_eq!(command.call((0,), "/x a").unwrap(), (true, false));
+    }
+
+    #[test]
+    fn regex_mismatch() {
+        struct Digits;
+
+        impl IterParser for Digits {
+            type Extract = ();
+            type ParserState = ();
+
+            fn parse<'p>(
+                &self,
+                _state: (),
+                _input: &'p str,
+            ) -> (Result<((), &'p str), ParseError>, Option<()>) {
+                (Ok(((), "")), None)
+            }
+
+            fn regex(&self) -> String {
+                String::from("[0-9]+")
+            }
+        }
+
+        let command: CommandSpec<(), (), _, _, _> = literal("/n")
+            .space()
+            .followed_by(Digits)
+            .on_call(|| move || ());
+        assert!(command.call((), "/n 12").is_ok());
+        let err = command.call((), "/n ab").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::RegexMismatch);
+        assert_eq!(err.range, 0..5);
+    }
+
+    #[test]
+    fn matches_newlines() {
+        let command = literal("/say")
+            .space()
+            .arg::<StringWildcard>()
+            .on_call(|message: StringWildcard| move |_: usize| message.get().to_string());
+        assert_eq!(command.call((0,), "/say a\nb").unwrap(), "a\nb");
+    }
+
+    #[test]
+    fn invalid_regex() {
+        struct Broken;
+
+        impl IterParser for Broken {
+            type Extract = ();
+            type ParserState = ();
+
+            fn parse<'p>(
+                &self,
+                _state: (),
+                input: &'p str,
+            ) -> (Result<((), &'p str), ParseError>, Option<()>) {
+                (Ok(((), input)), None)
+            }
+
+            fn regex(&self) -> String {
+                String::from("(")
+            }
+        }
+
+        let command: Result<CommandSpec<(usize,), usize, _, _, _>, _> = literal("/broken")
+            .followed_by(Broken)
+            .try_on_call(|| move |_: usize| 0);
+        assert!(command.is_err());
+
+        let command: Result<CommandSpec<(usize,), usize, _, _, _>, _> =
+            literal("/works").try_on_call(|| move |state: usize| state);
+        assert_eq!(command.unwrap().call((1,), "/works").unwrap(), 1);
+    }
+
+    #[test]
+    fn default_usage() {
+        struct Word;
+
+        impl IterParser for Word {
+            type Extract = ();
+            type ParserState = ();
+
+            fn parse<'p>(
+                &self,
+                _state: (),
+                _input: &'p str,
+            ) -> (Result<((), &'p str), ParseError>, Option<()>) {
+                (Ok(((), "")), None)
+            }
+
+            fn regex(&self) -> String {
+                String::from("\\S+")
+            }
+        }
+
+        struct Ping;
+
+        impl Command for Ping {
+            type GameState = ();
+            type CommandResult = ();
+
+            fn parse<'a>(&'a self, _input: &str) -> Result<Handler<'a, (), ()>, ParseError> {
+                Ok(Box::new(|_| ()))
+            }
+
+            fn regex(&self) -> String {
+                String::from("/ping")
+            }
+        }
+
+        let command: CommandSpec<(), (), _, _, _> = literal("/say")
+            .space()
+            .followed_by(Word)
+            .on_call(|| move || ());
+        assert_eq!(command.usage(), "/say <argument>");
+        assert_eq!(Ping.usage(), "/ping");
+        assert!(Ping.suggest("/pi", 3).is_empty());
+    }
 }
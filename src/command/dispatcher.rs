@@ -0,0 +1,737 @@
+use std::{fmt, mem};
+
+use super::redirect::{redirected, Redirect};
+use super::{before_cursor, Command, CommandId, Handler};
+use crate::graph::NodeKind;
+use crate::parser::{ParseError, ParseErrorKind, Source, Suggestion};
+use crate::regex::{checksum, CmdPos, LoadError, Minimized, DFA, NFA};
+
+pub type BoxedCommand<GameState, CommandResult> =
+    Box<dyn Command<GameState = GameState, CommandResult = CommandResult>>;
+
+pub type BoxedRedirect<GameState> = Box<dyn Redirect<GameState = GameState>>;
+
+/// A registered command, or the start of one that redirects back to the dispatcher.
+enum Entry<GameState, CommandResult> {
+    Command(BoxedCommand<GameState, CommandResult>),
+    Redirect(BoxedRedirect<GameState>),
+}
+
+impl<GameState, CommandResult> Entry<GameState, CommandResult> {
+    fn regex(&self) -> String {
+        match self {
+            Entry::Command(command) => command.regex(),
+            Entry::Redirect(redirect) => redirect.regex(),
+        }
+    }
+
+    fn usage(&self, source: Source<'_>) -> String {
+        match self {
+            Entry::Command(command) => command.usage_as(source),
+            Entry::Redirect(redirect) => redirect.usage_as(source),
+        }
+    }
+
+    fn help(&self, source: Source<'_>) -> String {
+        match self {
+            Entry::Command(command) => command.help_as(source),
+            Entry::Redirect(redirect) => match redirect.description() {
+                Some(description) => format!("{} - {}", redirect.usage_as(source), description),
+                None => redirect.usage_as(source),
+            },
+        }
+    }
+
+    fn permitted(&self, source: Source<'_>) -> bool {
+        match self {
+            Entry::Command(command) => command.permitted(source),
+            Entry::Redirect(redirect) => redirect.permitted(source),
+        }
+    }
+}
+
+/// Two commands that both match an input, found by [`Dispatcher::ambiguities`]. Which of them runs for such an
+/// input depends on the order they were registered in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ambiguity {
+    pub first: CommandId,
+    pub second: CommandId,
+    /// One of the shortest inputs that both commands match.
+    pub example: String,
+}
+
+impl fmt::Display for Ambiguity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Commands {} and {} both match {:?}",
+            self.first.id, self.second.id, self.example
+        )
+    }
+}
+
+/// Holds many commands, and routes an input to the commands that could possibly parse it.
+///
+/// Every registered command contributes its regex to one combined early termination dfa. When called,
+/// the dfa tells us which commands are candidates for the input, so only their parsers are run.
+/// Building the dfa is expensive, so it is only done when calling [`Dispatcher::compile`]. Until then
+/// every command is a candidate.
+pub struct Dispatcher<GameState, CommandResult> {
+    commands: Vec<Entry<GameState, CommandResult>>,
+    nfa: NFA<CmdPos<CommandId>>,
+    dfa: Option<DFA<CmdPos<CommandId>>>,
+}
+
+impl<GameState, CommandResult> Default for Dispatcher<GameState, CommandResult> {
+    fn default() -> Self {
+        Self {
+            commands: vec![],
+            nfa: NFA::empty(),
+            dfa: None,
+        }
+    }
+}
+
+impl<GameState, CommandResult> Dispatcher<GameState, CommandResult> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the command to the dispatcher, and returns the id it was registered under.
+    /// Fails if the regex of the command uses a feature we can't convert into a nfa.
+    pub fn register<C>(&mut self, command: C) -> anyhow::Result<CommandId>
+    where
+        C: Command<GameState = GameState, CommandResult = CommandResult> + 'static,
+    {
+        self.add(Entry::Command(Box::new(command)))
+    }
+
+    /// Adds the start of a command that continues with any command of this dispatcher, like
+    /// `/execute as @p run`, see [`CommandBuilder::redirect`](super::builder::CommandBuilder::redirect).
+    /// The redirected command may leave out the leading `/`, as clients send it that way.
+    pub fn register_redirect<R>(&mut self, redirect: R) -> anyhow::Result<CommandId>
+    where
+        R: Redirect<GameState = GameState> + 'static,
+    {
+        self.add(Entry::Redirect(Box::new(redirect)))
+    }
+
+    fn add(&mut self, entry: Entry<GameState, CommandResult>) -> anyhow::Result<CommandId> {
+        let id = CommandId::of(self.commands.len());
+        let nfa = NFA::<CmdPos<CommandId>>::from_command_regex(&entry.regex(), id)?;
+
+        self.nfa = if self.commands.is_empty() {
+            nfa
+        } else {
+            mem::replace(&mut self.nfa, NFA::empty()).or(nfa)?
+        };
+        self.dfa = None;
+        self.commands.push(entry);
+
+        Ok(id)
+    }
+
+    /// Builds the dfa used to route inputs from all the commands registered so far, and minimizes it. Returns
+    /// the number of states before and after minimizing.
+    pub fn compile(&mut self) -> Minimized {
+        let mut dfa = self.nfa.clone().into_early_termination_dfa();
+        let minimized = dfa.minimize();
+        self.dfa = Some(dfa);
+        minimized
+    }
+
+    pub fn is_compiled(&self) -> bool {
+        self.dfa.is_some()
+    }
+
+    /// A checksum of the regexes of the registered commands, in order. It changes whenever the commands do, and
+    /// is saved with the compiled dfa so a stale one is never loaded.
+    pub fn checksum(&self) -> u64 {
+        checksum(self.commands.iter().map(Entry::regex))
+    }
+
+    /// Returns the compiled dfa as bytes, to be cached on disk or made at build time, and loaded with
+    /// [`Dispatcher::load_compiled`] instead of compiling again. Returns None if the dispatcher isn't compiled.
+    pub fn save_compiled(&self) -> Option<Vec<u8>> {
+        let dfa = self.dfa.as_ref()?;
+        Some(dfa.to_bytes(self.checksum()))
+    }
+
+    /// Uses a dfa saved with [`Dispatcher::save_compiled`], so the dispatcher is compiled without building it.
+    /// Fails with [`LoadError::ChecksumMismatch`] if it was saved for other commands, in which case
+    /// [`Dispatcher::compile`] should be called instead. Fails with [`LoadError::Corrupt`] if the dfa leads to
+    /// commands that are not registered.
+    pub fn load_compiled(&mut self, bytes: &[u8]) -> Result<(), LoadError> {
+        let dfa: DFA<CmdPos<CommandId>> = DFA::from_bytes(bytes, self.checksum())?;
+        // The checksum only covers the commands, so damaged or edited bytes can still name ids past the end.
+        let unknown = dfa
+            .states
+            .iter()
+            .flat_map(|state| &state.associations)
+            .any(|(CmdPos::End(id) | CmdPos::Mid(id))| id.id >= self.commands.len());
+        if unknown {
+            return Err(LoadError::Corrupt);
+        }
+        self.dfa = Some(dfa);
+        Ok(())
+    }
+
+    /// Returns the command registered under the id, or None if it is a redirect.
+    pub fn get(&self, id: CommandId) -> Option<&BoxedCommand<GameState, CommandResult>> {
+        match self.commands.get(id.id) {
+            Some(Entry::Command(command)) => Some(command),
+            _ => None,
+        }
+    }
+
+    /// The number of registered commands and redirects.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Returns the ids of the commands that might be able to parse the input, in the order they were registered.
+    pub fn candidates(&self, input: &str) -> Vec<CommandId> {
+        let dfa = match &self.dfa {
+            Some(dfa) => dfa,
+            None => return (0..self.commands.len()).map(CommandId::of).collect(),
+        };
+
+        let mut candidates = match dfa.early_termination_find(input) {
+            Ok(ends) => ends,
+            // None of the commands matched the entire input, but the ones we passed through
+            // on the way are still the best source for an error message.
+            Err(mids) => mids,
+        };
+        candidates.sort_by_key(|id| id.id);
+        candidates.dedup();
+        candidates
+    }
+
+    /// Returns the ids of the commands that the input could be the start of, in the order they were registered.
+    /// These are the commands that can complete the input, which includes the ones that continue a command
+    /// that ends at the input.
+    fn completion_candidates(&self, input: &str) -> Vec<CommandId> {
+        let dfa = match &self.dfa {
+            Some(dfa) => dfa,
+            None => return (0..self.commands.len()).map(CommandId::of).collect(),
+        };
+
+        let mut candidates = dfa.early_termination_prefix_find(input);
+        candidates.sort_by_key(|id| id.id);
+        candidates.dedup();
+        candidates
+    }
+
+    /// Finds every pair of registered commands whose regexes match the same input, with an example of such an
+    /// input. Regexes can match more than their command accepts, so an example may still be rejected by one of
+    /// the commands. This builds the dfa of all the commands, which can take a while, so it is meant for startup
+    /// checks and tests.
+    pub fn ambiguities(&self) -> Vec<Ambiguity> {
+        if self.commands.is_empty() {
+            return Vec::new();
+        }
+
+        let dfa: DFA<CmdPos<CommandId>> = self.nfa.clone().into();
+        dfa.overlapping_ends()
+            .into_iter()
+            .map(|((first, second), example)| Ambiguity {
+                first,
+                second,
+                example: String::from_utf8_lossy(&example).into_owned(),
+            })
+            .collect()
+    }
+
+    /// Parses the command a redirect continues with. Without a leading `/` it is parsed as if it had one, and
+    /// the ranges of errors are moved back to the input as written.
+    fn parse_redirected<'a>(
+        &'a self,
+        source: Source<'_>,
+        input: &str,
+    ) -> Result<Handler<'a, GameState, CommandResult>, ParseError> {
+        if input.starts_with('/') {
+            return self.parse_as(source, input);
+        }
+        self.parse_as(source, &format!("/{}", input))
+            .map_err(|mut err| {
+                err.range = err.range.start.saturating_sub(1)..err.range.end.saturating_sub(1);
+                err
+            })
+    }
+
+    /// Suggests completions for the command a redirect continues with, see `parse_redirected`.
+    fn suggest_redirected(&self, source: Source<'_>, input: &str) -> Vec<Suggestion> {
+        if input.starts_with('/') {
+            return self.suggest_as(source, input, input.len());
+        }
+        self.suggest_as(source, &format!("/{}", input), input.len() + 1)
+            .into_iter()
+            .map(|mut suggestion| {
+                if suggestion.range.start == 0 {
+                    suggestion.text = suggestion.text.trim_start_matches('/').to_string();
+                }
+                suggestion.range = suggestion.range.start.saturating_sub(1)
+                    ..suggestion.range.end.saturating_sub(1);
+                suggestion
+            })
+            .collect()
+    }
+}
+
+impl<GameState, CommandResult> Command for Dispatcher<GameState, CommandResult> {
+    type GameState = GameState;
+    type CommandResult = CommandResult;
+
+    fn parse<'a>(
+        &'a self,
+        input: &str,
+    ) -> Result<Handler<'a, GameState, CommandResult>, ParseError> {
+        self.parse_as(Source::NONE, input)
+    }
+
+    fn parse_as<'a>(
+        &'a self,
+        source: Source<'_>,
+        input: &str,
+    ) -> Result<Handler<'a, GameState, CommandResult>, ParseError> {
+        let mut error = None;
+
+        for id in self.candidates(input) {
+            let redirect = match &self.commands[id.id] {
+                Entry::Command(command) => match command.parse_as(source, input) {
+                    Ok(handler) => return Ok(handler),
+                    Err(err) => {
+                        error = Some(ParseError::furthest(error, err));
+                        continue;
+                    }
+                },
+                Entry::Redirect(redirect) => redirect,
+            };
+
+            let alternatives = match redirect.parse_as(source, input) {
+                Ok(alternatives) => alternatives,
+                Err(err) => {
+                    error = Some(ParseError::furthest(error, err));
+                    continue;
+                }
+            };
+            for (modifier, start) in alternatives {
+                match self.parse_redirected(source, &input[start..]) {
+                    Ok(handler) => return Ok(redirected(modifier, handler)),
+                    Err(err) => error = Some(ParseError::furthest(error, err.shift(start, 0))),
+                }
+            }
+        }
+
+        match error {
+            Some(err) => Err(err),
+            None => Err(ParseError::at_token(ParseErrorKind::UnknownCommand, input)),
+        }
+    }
+
+    fn suggest(&self, input: &str, cursor: usize) -> Vec<Suggestion> {
+        self.suggest_as(Source::NONE, input, cursor)
+    }
+
+    fn suggest_as(&self, source: Source<'_>, input: &str, cursor: usize) -> Vec<Suggestion> {
+        let input = before_cursor(input, cursor);
+        let mut suggestions = Vec::new();
+
+        for id in self.completion_candidates(input) {
+            let found = match &self.commands[id.id] {
+                Entry::Command(command) => command.suggest_as(source, input, input.len()),
+                Entry::Redirect(redirect) => {
+                    let mut found = redirect.suggest_as(source, input, input.len());
+                    for (_, start) in redirect.parse_as(source, input).unwrap_or_default() {
+                        found.extend(
+                            self.suggest_redirected(source, &input[start..])
+                                .into_iter()
+                                .map(|suggestion| suggestion.shift(start)),
+                        );
+                    }
+                    found
+                }
+            };
+            for suggestion in found {
+                if !suggestions.contains(&suggestion) {
+                    suggestions.push(suggestion);
+                }
+            }
+        }
+
+        suggestions
+    }
+
+    fn regex(&self) -> String {
+        self.commands
+            .iter()
+            .map(|command| format!("({})", command.regex()))
+            .collect::<Vec<String>>()
+            .join("|")
+    }
+
+    fn usage(&self) -> String {
+        self.usage_as(Source::NONE)
+    }
+
+    /// The usage of every command permitted to `source`, one on each line.
+    fn usage_as(&self, source: Source<'_>) -> String {
+        self.commands
+            .iter()
+            .filter(|command| command.permitted(source))
+            .map(|command| command.usage(source))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        self.nodes_as(Source::NONE)
+    }
+
+    fn nodes_as(&self, source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        self.commands
+            .iter()
+            .flat_map(|command| match command {
+                Entry::Command(command) => command.nodes_as(source),
+                Entry::Redirect(_) => Vec::new(),
+            })
+            .collect()
+    }
+
+    fn redirects(&self) -> Vec<Vec<NodeKind>> {
+        self.redirects_as(Source::NONE)
+    }
+
+    fn redirects_as(&self, source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        self.commands
+            .iter()
+            .flat_map(|command| match command {
+                Entry::Command(command) => command.redirects_as(source),
+                Entry::Redirect(redirect) => redirect.nodes_as(source),
+            })
+            .collect()
+    }
+
+    /// The help text of every command permitted to `source`, in the order they were registered.
+    fn help_as(&self, source: Source<'_>) -> String {
+        self.commands
+            .iter()
+            .filter(|command| command.permitted(source))
+            .map(|command| command.help(source))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ambiguity, Dispatcher, LoadError};
+    use crate::command::builder::{literal, CommandBuilder};
+    use crate::command::{Command, CommandId};
+    use crate::graph::CommandGraph;
+    use crate::parser::{ParseErrorKind, Suggestion};
+
+    fn dispatcher() -> Dispatcher<(usize,), usize> {
+        let mut dispatcher = Dispatcher::new();
+        dispatcher
+            .register(
+                literal("/tp")
+                    .space()
+                    .arg::<u32>()
+                    .on_call(|x: u32| move |state: usize| state + x as usize),
+            )
+            .unwrap();
+        dispatcher
+            .register(
+                literal("/tp")
+                    .space()
+                    .arg::<u32>()
+                    .space()
+                    .arg::<u32>()
+                    .on_call(|x: u32, y: u32| move |state: usize| state + (x * y) as usize),
+            )
+            .unwrap();
+        dispatcher
+            .register(literal("/kill").on_call(|| move |_state: usize| 0))
+            .unwrap();
+        dispatcher.compile();
+        dispatcher
+    }
+
+    #[test]
+    fn routes_to_single_command() {
+        let dispatcher = dispatcher();
+
+        assert_eq!(dispatcher.len(), 3);
+        assert_eq!(dispatcher.candidates("/kill"), vec![CommandId::of(2)]);
+        assert_eq!(dispatcher.call((10,), "/kill").unwrap(), 0);
+    }
+
+    #[test]
+    fn compile_minimizes() {
+        let mut dispatcher = dispatcher();
+        let minimized = dispatcher.compile();
+
+        assert!(minimized.states_after > 0);
+        assert!(minimized.states_after <= minimized.states_before);
+        assert_eq!(dispatcher.candidates("/kill"), vec![CommandId::of(2)]);
+        assert_eq!(dispatcher.call((10,), "/tp 5 3").unwrap(), 25);
+    }
+
+    #[test]
+    fn load_compiled() {
+        let commands = |names: &[&str]| {
+            let mut dispatcher = Dispatcher::<(), usize>::new();
+            for (i, name) in names.iter().enumerate() {
+                dispatcher
+                    .register(literal(name).on_call(move || move || i))
+                    .unwrap();
+            }
+            dispatcher
+        };
+
+        let mut compiled = commands(&["/kill", "/heal"]);
+        assert_eq!(compiled.save_compiled(), None);
+        compiled.compile();
+        let bytes = compiled.save_compiled().unwrap();
+
+        let mut loaded = commands(&["/kill", "/heal"]);
+        loaded.load_compiled(&bytes).unwrap();
+        assert!(loaded.is_compiled());
+        assert_eq!(loaded.candidates("/heal"), vec![CommandId::of(1)]);
+        assert_eq!(loaded.call((), "/heal").unwrap(), 1);
+
+        // A dfa saved for other commands is not used.
+        let mut changed = commands(&["/heal", "/kill"]);
+        assert!(matches!(
+            changed.load_compiled(&bytes),
+            Err(LoadError::ChecksumMismatch { .. })
+        ));
+        assert!(!changed.is_compiled());
+
+        // A dfa that leads to more commands than are registered is rejected, even with the right checksum.
+        let mut more = commands(&["/kill", "/heal", "/tp"]);
+        more.compile();
+        let mut fewer = commands(&["/kill", "/heal"]);
+        let bytes = more.dfa.as_ref().unwrap().to_bytes(fewer.checksum());
+        assert_eq!(fewer.load_compiled(&bytes), Err(LoadError::Corrupt));
+        assert!(!fewer.is_compiled());
+    }
+
+    #[test]
+    fn routes_between_shared_prefix() {
+        let dispatcher = dispatcher();
+
+        assert_eq!(dispatcher.call((10,), "/tp 5").unwrap(), 15);
+        assert_eq!(dispatcher.call((10,), "/tp 5 3").unwrap(), 25);
+    }
+
+    #[test]
+    fn unknown_and_invalid() {
+        let dispatcher = dispatcher();
+
+        assert!(dispatcher.call((10,), "/gamemode").is_err());
+        assert!(dispatcher.call((10,), "/tp abc").is_err());
+        assert!(dispatcher.call((10,), "/kill me").is_err());
+    }
+
+    #[test]
+    fn uncompiled() {
+        let mut dispatcher = Dispatcher::<(usize,), usize>::new();
+        dispatcher
+            .register(literal("/kill").on_call(|| move |_state: usize| 0))
+            .unwrap();
+
+        assert!(!dispatcher.is_compiled());
+        assert_eq!(dispatcher.candidates("/tp"), vec![CommandId::of(0)]);
+        assert_eq!(dispatcher.call((10,), "/kill").unwrap(), 0);
+        assert!(dispatcher.call((10,), "/tp").is_err());
+    }
+
+    #[test]
+    fn empty() {
+        let dispatcher = Dispatcher::<(), ()>::new();
+
+        assert!(dispatcher.is_empty());
+        let err = dispatcher.call((), "/tp").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnknownCommand);
+        assert_eq!(dispatcher.regex(), "");
+    }
+
+    #[test]
+    fn suggest() {
+        let mut dispatcher = dispatcher();
+        dispatcher
+            .register(
+                literal("/tp")
+                    .space()
+                    .literal("spawn")
+                    .on_call(|| move |_state: usize| 0),
+            )
+            .unwrap();
+        dispatcher.compile();
+
+        assert_eq!(
+            dispatcher.suggest("/k", 2),
+            vec![Suggestion::new(0..2, String::from("/kill"))]
+        );
+        assert_eq!(
+            dispatcher.suggest("/", 1),
+            vec![
+                Suggestion::new(0..1, String::from("/tp")),
+                Suggestion::new(0..1, String::from("/kill")),
+            ]
+        );
+        assert_eq!(
+            dispatcher.suggest("/tp sp", 6),
+            vec![Suggestion::new(4..6, String::from("spawn"))]
+        );
+        assert!(dispatcher.suggest("/tp 5", 5).is_empty());
+
+        // A command that ends at the input doesn't hide the longer ones from completion.
+        let mut gamemode = Dispatcher::<(), ()>::new();
+        gamemode
+            .register(literal("/gm").on_call(|| move || ()))
+            .unwrap();
+        gamemode
+            .register(literal("/gmx").on_call(|| move || ()))
+            .unwrap();
+        let expected = vec![
+            Suggestion::new(0..3, String::from("/gm")),
+            Suggestion::new(0..3, String::from("/gmx")),
+        ];
+        assert_eq!(gamemode.suggest("/gm", 3), expected);
+        gamemode.compile();
+        assert_eq!(gamemode.suggest("/gm", 3), expected);
+    }
+
+    #[test]
+    fn help() {
+        let dispatcher = dispatcher();
+
+        assert_eq!(dispatcher.usage(), "/tp <u32>\n/tp <u32> <u32>\n/kill");
+        assert_eq!(dispatcher.help(), dispatcher.usage());
+    }
+
+    #[test]
+    fn aliases() {
+        let mut dispatcher = Dispatcher::<(usize,), usize>::new();
+        dispatcher
+            .register(
+                literal("/tp")
+                    .alias("/teleport")
+                    .space()
+                    .arg::<u32>()
+                    .on_call(|x: u32| move |state: usize| state + x as usize),
+            )
+            .unwrap();
+        dispatcher.compile();
+
+        assert_eq!(dispatcher.call((1,), "/tp 5").unwrap(), 6);
+        assert_eq!(dispatcher.call((1,), "/teleport 5").unwrap(), 6);
+        assert!(dispatcher.call((1,), "/tele 5").is_err());
+        assert_eq!(dispatcher.usage(), "(/tp|/teleport) <u32>");
+        assert_eq!(
+            dispatcher.suggest("/t", 2),
+            vec![
+                Suggestion::new(0..2, String::from("/tp")),
+                Suggestion::new(0..2, String::from("/teleport")),
+            ]
+        );
+
+        let graph = CommandGraph::from_command(&dispatcher);
+        let teleport = graph.child(0, "teleport").unwrap();
+        assert!(graph.child(teleport, "u32").is_some());
+    }
+
+    #[test]
+    fn redirects() {
+        let mut dispatcher = dispatcher();
+        dispatcher
+            .register_redirect(
+                literal("/execute")
+                    .space()
+                    .literal("add")
+                    .space()
+                    .arg::<u32>()
+                    .space()
+                    .literal("run")
+                    .redirect(|x: u32| move |state: usize| (state + x as usize,)),
+            )
+            .unwrap();
+        dispatcher.compile();
+
+        assert_eq!(
+            dispatcher.call((10,), "/execute add 1 run tp 5").unwrap(),
+            16
+        );
+        assert_eq!(
+            dispatcher.call((10,), "/execute add 1 run /tp 5").unwrap(),
+            16
+        );
+        assert_eq!(
+            dispatcher
+                .call((10,), "/execute add 1 run execute add 2 run tp 5")
+                .unwrap(),
+            18
+        );
+
+        let err = dispatcher
+            .call((10,), "/execute add 1 run tp x")
+            .unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidNumber);
+        assert_eq!(err.range, 22..23);
+        let err = dispatcher.call((10,), "/execute add 1 run").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::ExpectedSpace);
+
+        assert_eq!(
+            dispatcher.suggest("/execute add 1 run k", 20),
+            vec![Suggestion::new(19..20, String::from("kill"))]
+        );
+        assert_eq!(
+            dispatcher.usage(),
+            "/tp <u32>\n/tp <u32> <u32>\n/kill\n/execute add <u32> run <command>"
+        );
+
+        let graph = CommandGraph::from_command(&dispatcher);
+        let execute = graph.child(0, "execute").unwrap();
+        let add = graph.child(execute, "add").unwrap();
+        let x = graph.child(add, "u32").unwrap();
+        let run = graph.child(x, "run").unwrap();
+        assert_eq!(graph.nodes()[run].redirect, Some(0));
+        assert!(!graph.nodes()[run].executable);
+    }
+
+    #[test]
+    fn ambiguities() {
+        let mut dispatcher = dispatcher();
+        assert!(dispatcher.ambiguities().is_empty());
+
+        dispatcher
+            .register(
+                literal("/tp")
+                    .space()
+                    .arg::<String>()
+                    .on_call(|_name: String| move |_state: usize| 0),
+            )
+            .unwrap();
+        let ambiguities = dispatcher.ambiguities();
+        assert_eq!(
+            ambiguities,
+            vec![Ambiguity {
+                first: CommandId::of(0),
+                second: CommandId::of(3),
+                example: String::from("/tp +0"),
+            }]
+        );
+        assert_eq!(
+            ambiguities[0].to_string(),
+            "Commands 0 and 3 both match \"/tp +0\""
+        );
+        assert!(Dispatcher::<(), ()>::new().ambiguities().is_empty());
+    }
+}
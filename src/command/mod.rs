@@ -1,12 +1,17 @@
 pub mod builder;
+pub mod dispatcher;
+pub mod redirect;
 
 use std::marker::PhantomData;
 
-use anyhow::bail;
+use crate::{
+    generic::Func,
+    graph::NodeKind,
+    parser::{ArgumentInfo, IterParser, ParseError, ParseErrorKind, Source, Suggestion},
+    regex::Association,
+};
 
-use crate::{generic::Func, parser::IterParser};
-
-#[derive(Clone, Copy, Default, PartialEq, Eq, std::hash::Hash, Debug)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, std::hash::Hash, Debug)]
 pub struct CommandId {
     pub(crate) id: usize,
 }
@@ -17,21 +22,170 @@ impl CommandId {
     }
 }
 
+impl Association for CommandId {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.id.write(out);
+    }
+
+    fn read(bytes: &mut &[u8]) -> Option<Self> {
+        usize::read(bytes).map(Self::of)
+    }
+}
+
+/// A handler that has already parsed its arguments, and only needs the game state to run.
+pub type Handler<'a, GameState, CommandResult> = Box<dyn FnOnce(GameState) -> CommandResult + 'a>;
+
 pub trait Command {
     type GameState;
     type CommandResult;
-    fn call(&self, gamestate: Self::GameState, input: &str) -> anyhow::Result<Self::CommandResult>;
+
+    /// Parses the input without running the command. This lets a caller try several commands on the same
+    /// input, and only hand over the game state to the one that actually matched.
+    /// The range of a returned error is relative to the whole input.
+    fn parse<'a>(
+        &'a self,
+        input: &str,
+    ) -> Result<Handler<'a, Self::GameState, Self::CommandResult>, ParseError>;
+
+    /// Like [`Command::parse`], but checks the requirements of the command against `source`, see
+    /// [`Require`](crate::parser::Require). Commands without requirements can use the default implementation.
+    fn parse_as<'a>(
+        &'a self,
+        _source: Source<'_>,
+        input: &str,
+    ) -> Result<Handler<'a, Self::GameState, Self::CommandResult>, ParseError> {
+        self.parse(input)
+    }
+
+    /// Parses the input and runs the command with the game state, see [`Command::parse`].
+    fn call(
+        &self,
+        gamestate: Self::GameState,
+        input: &str,
+    ) -> Result<Self::CommandResult, ParseError> {
+        self.call_as(Source::NONE, gamestate, input)
+    }
+
+    /// Parses the input as `source` and runs the command with the game state, see [`Command::parse_as`].
+    fn call_as(
+        &self,
+        source: Source<'_>,
+        gamestate: Self::GameState,
+        input: &str,
+    ) -> Result<Self::CommandResult, ParseError> {
+        let handler = self.parse_as(source, input)?;
+        Ok(handler(gamestate))
+    }
+
+    /// Returns the ways the input could be completed at the cursor. Everything after the cursor is ignored,
+    /// and the ranges of the suggestions are byte ranges into the input. Suggests nothing by default.
+    fn suggest(&self, _input: &str, _cursor: usize) -> Vec<Suggestion> {
+        Vec::new()
+    }
+
+    /// Like [`Command::suggest`], but leaves out what `source` is not permitted to use.
+    fn suggest_as(&self, _source: Source<'_>, input: &str, cursor: usize) -> Vec<Suggestion> {
+        self.suggest(input, cursor)
+    }
+
     fn regex(&self) -> String;
+
+    /// Returns the usage of the command generated from its parsers, like `/tp <x> <y> <z> [<reason>]`.
+    /// Commands that don't know their parsers fall back to their regex.
+    fn usage(&self) -> String {
+        self.regex()
+    }
+
+    /// Like [`Command::usage`], but leaves out what `source` is not permitted to use.
+    fn usage_as(&self, _source: Source<'_>) -> String {
+        self.usage()
+    }
+
+    fn description(&self) -> Option<&str> {
+        None
+    }
+
+    /// Whether `source` meets the requirements of at least part of the command. Commands that are not
+    /// permitted are left out of the usage and help of a dispatcher.
+    fn permitted(&self, _source: Source<'_>) -> bool {
+        true
+    }
+
+    /// Returns the named arguments of the command, in the order they appear.
+    fn arguments(&self) -> Vec<&ArgumentInfo> {
+        Vec::new()
+    }
+
+    /// Returns the sequences of Brigadier nodes that make up the command, see [`CommandGraph`](crate::graph::CommandGraph).
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        Vec::new()
+    }
+
+    /// Like [`Command::nodes`], but leaves out what `source` is not permitted to use.
+    fn nodes_as(&self, _source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        self.nodes()
+    }
+
+    /// Returns the sequences of nodes whose last node redirects to the root of the graph, see
+    /// [`Redirect`](redirect::Redirect).
+    fn redirects(&self) -> Vec<Vec<NodeKind>> {
+        Vec::new()
+    }
+
+    /// Like [`Command::redirects`], but leaves out what `source` is not permitted to use.
+    fn redirects_as(&self, _source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        self.redirects()
+    }
+
+    /// Renders the usage and description of the command, followed by a line for every described argument.
+    fn help(&self) -> String {
+        self.help_as(Source::NONE)
+    }
+
+    /// Like [`Command::help`], but leaves out what `source` is not permitted to use.
+    fn help_as(&self, source: Source<'_>) -> String {
+        let mut help = self.usage_as(source);
+        if let Some(description) = self.description() {
+            help.push_str(" - ");
+            help.push_str(description);
+        }
+        for argument in self.arguments() {
+            if let Some(description) = &argument.description {
+                help.push_str(&format!("\n    <{}>: {}", argument.name, description));
+            }
+        }
+        help
+    }
+}
+
+/// Cuts the input at the cursor, moving the cursor back if it is not on a char boundary.
+pub(crate) fn before_cursor(input: &str, cursor: usize) -> &str {
+    let mut cursor = cursor.min(input.len());
+    while !input.is_char_boundary(cursor) {
+        cursor -= 1;
+    }
+    &input[..cursor]
 }
 
 pub struct CommandSpec<GameState, CommandResult, F1, F2, P> {
     pub(crate) parser: P,
     pub(crate) mapping: F1,
+    /// The regex of the parser, anchored at both ends. `.` also matches newlines.
+    pub(crate) regex: regex::Regex,
+    pub(crate) description: Option<String>,
     pub(crate) gamestate: PhantomData<GameState>,
     pub(crate) command_result: PhantomData<CommandResult>,
     pub(crate) mapping_result: PhantomData<F2>,
 }
 
+impl<GameState, CommandResult, F1, F2, P> CommandSpec<GameState, CommandResult, F1, F2, P> {
+    /// Sets the description shown in the help text of the command.
+    pub fn describe(mut self, description: &str) -> Self {
+        self.description = Some(String::from(description));
+        self
+    }
+}
+
 impl<CommandResult, P: IterParser, GameState, F1, F2, Ext> Command
     for CommandSpec<GameState, CommandResult, F1, F2, P>
 where
@@ -42,29 +196,90 @@ where
     type GameState = GameState;
     type CommandResult = CommandResult;
 
-    fn call(&self, gamestate: GameState, input: &str) -> anyhow::Result<CommandResult> {
+    fn parse<'a>(
+        &'a self,
+        input: &str,
+    ) -> Result<Handler<'a, GameState, CommandResult>, ParseError> {
+        self.parse_as(Source::NONE, input)
+    }
+
+    fn parse_as<'a>(
+        &'a self,
+        source: Source<'_>,
+        input: &str,
+    ) -> Result<Handler<'a, GameState, CommandResult>, ParseError> {
         let mut state = P::ParserState::default();
 
-        let regex = regex::Regex::new(&*self.parser.regex()).unwrap();
+        // The parser might backtrack many times, we report the error that got the furthest into the input.
+        let mut error = None;
 
         loop {
-            match self.parser.parse(state, input) {
-                (Ok((ext, _)), _) => {
-                    if !regex.replace(input, "").is_empty() {
-                        // Only check if we have reached the end of the input to get more specific error messages.
-                        bail!("Too many arguments");
+            let (result, next_state) = self.parser.parse_as(source, state, input);
+            let err = match result {
+                Ok((_, out)) if !out.is_empty() => {
+                    // Another attempt might still use up the rest of the input.
+                    let start = input.len() - out.len();
+                    ParseError::new(ParseErrorKind::TooManyArguments, start..input.len())
+                }
+                Ok((ext, _)) => {
+                    // The regex should match everything the parsers accept. When a parser gives one that is too
+                    // narrow, a compiled dispatcher would never route this input here, so it is not accepted here
+                    // either.
+                    if self.regex.is_match(input) {
+                        let handler = self.mapping.call(ext);
+                        return Ok(Box::new(move |gamestate| handler.call(gamestate)));
                     }
-
-                    return Ok(self.mapping.call(ext).call(gamestate));
+                    ParseError::new(ParseErrorKind::RegexMismatch, 0..input.len())
                 }
-                (Err(e), None) => {
-                    bail!("Not able to parse input: {:?}", e);
+                Err(err) => err,
+            };
+
+            match next_state {
+                Some(next_state) => {
+                    error = Some(ParseError::furthest(error, err));
+                    state = next_state;
                 }
-                (Err(_), Some(next_state)) => state = next_state,
+                None => return Err(ParseError::furthest(error, err)),
             }
         }
     }
 
+    fn suggest(&self, input: &str, cursor: usize) -> Vec<Suggestion> {
+        self.suggest_as(Source::NONE, input, cursor)
+    }
+
+    fn suggest_as(&self, source: Source<'_>, input: &str, cursor: usize) -> Vec<Suggestion> {
+        self.parser.suggest_as(source, before_cursor(input, cursor))
+    }
+
+    fn usage(&self) -> String {
+        self.usage_as(Source::NONE)
+    }
+
+    fn usage_as(&self, source: Source<'_>) -> String {
+        self.parser.usage_as(source).trim_end().to_string()
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    fn permitted(&self, source: Source<'_>) -> bool {
+        self.parser.permitted(source)
+    }
+
+    fn arguments(&self) -> Vec<&ArgumentInfo> {
+        self.parser.arguments()
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        self.nodes_as(Source::NONE)
+    }
+
+    fn nodes_as(&self, source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        self.parser.nodes_as(source)
+    }
+
     fn regex(&self) -> String {
         self.parser.regex()
     }
@@ -0,0 +1,177 @@
+use std::marker::PhantomData;
+
+use super::{before_cursor, Handler};
+use crate::{
+    generic::Func,
+    graph::NodeKind,
+    parser::{IterParser, ParseError, Source, Suggestion},
+};
+
+/// Changes the game state before it is handed to the command that was redirected to.
+pub type Modifier<'a, GameState> = Box<dyn FnOnce(GameState) -> GameState + 'a>;
+
+/// The start of a command that continues with any other command of a
+/// [`Dispatcher`](super::dispatcher::Dispatcher), like `/execute as @p run`. It is registered with
+/// [`Dispatcher::register_redirect`](super::dispatcher::Dispatcher::register_redirect).
+pub trait Redirect {
+    type GameState;
+
+    /// Parses the start of the input, returning every way it could be parsed as how it changes the game state and
+    /// where the redirected command starts. The range of a returned error is relative to the whole input.
+    #[allow(clippy::type_complexity)]
+    fn parse<'a>(
+        &'a self,
+        input: &str,
+    ) -> Result<Vec<(Modifier<'a, Self::GameState>, usize)>, ParseError>;
+
+    /// Like [`Redirect::parse`], but checks the requirements of the start against `source`.
+    #[allow(clippy::type_complexity)]
+    fn parse_as<'a>(
+        &'a self,
+        _source: Source<'_>,
+        input: &str,
+    ) -> Result<Vec<(Modifier<'a, Self::GameState>, usize)>, ParseError> {
+        self.parse(input)
+    }
+
+    /// Returns the ways the start of the input could be completed at the cursor.
+    fn suggest(&self, input: &str, cursor: usize) -> Vec<Suggestion>;
+
+    /// Like [`Redirect::suggest`], but leaves out what `source` is not permitted to use.
+    fn suggest_as(&self, _source: Source<'_>, input: &str, cursor: usize) -> Vec<Suggestion> {
+        self.suggest(input, cursor)
+    }
+
+    /// The regex of the start, followed by anything.
+    fn regex(&self) -> String;
+
+    /// Returns the usage of the start, followed by `<command>`.
+    fn usage(&self) -> String;
+
+    /// Like [`Redirect::usage`], but leaves out what `source` is not permitted to use.
+    fn usage_as(&self, _source: Source<'_>) -> String {
+        self.usage()
+    }
+
+    fn description(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the sequences of nodes of the start. The last node of each redirects to the root of the graph.
+    fn nodes(&self) -> Vec<Vec<NodeKind>>;
+
+    /// Like [`Redirect::nodes`], but leaves out what `source` is not permitted to use.
+    fn nodes_as(&self, _source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        self.nodes()
+    }
+
+    /// Whether `source` meets the requirements of the start, see [`Command::permitted`](super::Command::permitted).
+    fn permitted(&self, _source: Source<'_>) -> bool {
+        true
+    }
+}
+
+/// Wraps the handler of the redirected command, so it gets the changed game state.
+pub(crate) fn redirected<'a, GameState: 'a, CommandResult: 'a>(
+    modifier: Modifier<'a, GameState>,
+    handler: Handler<'a, GameState, CommandResult>,
+) -> Handler<'a, GameState, CommandResult> {
+    Box::new(move |gamestate| handler(modifier(gamestate)))
+}
+
+pub struct RedirectSpec<GameState, F1, F2, P> {
+    pub(crate) parser: P,
+    pub(crate) modifier: F1,
+    pub(crate) description: Option<String>,
+    pub(crate) gamestate: PhantomData<GameState>,
+    pub(crate) modifier_result: PhantomData<F2>,
+}
+
+impl<GameState, F1, F2, P> RedirectSpec<GameState, F1, F2, P> {
+    /// Sets the description shown in the help text of the redirect.
+    pub fn describe(mut self, description: &str) -> Self {
+        self.description = Some(String::from(description));
+        self
+    }
+}
+
+impl<GameState, F1, F2, P, Ext> Redirect for RedirectSpec<GameState, F1, F2, P>
+where
+    F1: Func<Ext, Output = F2>,
+    F2: Func<GameState, Output = GameState>,
+    P: IterParser<Extract = Ext>,
+{
+    type GameState = GameState;
+
+    fn parse<'a>(
+        &'a self,
+        input: &str,
+    ) -> Result<Vec<(Modifier<'a, GameState>, usize)>, ParseError> {
+        self.parse_as(Source::NONE, input)
+    }
+
+    fn parse_as<'a>(
+        &'a self,
+        source: Source<'_>,
+        input: &str,
+    ) -> Result<Vec<(Modifier<'a, GameState>, usize)>, ParseError> {
+        let mut state = Some(P::ParserState::default());
+        let mut error = None;
+        let mut alternatives = Vec::new();
+
+        while let Some(current) = state {
+            let (result, next) = self.parser.parse_as(source, current, input);
+            match result {
+                Ok((ext, out)) => {
+                    let modifier = self.modifier.call(ext);
+                    let modifier: Modifier<'a, GameState> =
+                        Box::new(move |gamestate| modifier.call(gamestate));
+                    alternatives.push((modifier, input.len() - out.len()));
+                }
+                Err(err) => error = Some(ParseError::furthest(error, err)),
+            }
+            state = next;
+        }
+
+        match error {
+            Some(err) if alternatives.is_empty() => Err(err),
+            _ => Ok(alternatives),
+        }
+    }
+
+    fn suggest(&self, input: &str, cursor: usize) -> Vec<Suggestion> {
+        self.suggest_as(Source::NONE, input, cursor)
+    }
+
+    fn suggest_as(&self, source: Source<'_>, input: &str, cursor: usize) -> Vec<Suggestion> {
+        self.parser.suggest_as(source, before_cursor(input, cursor))
+    }
+
+    fn regex(&self) -> String {
+        format!("({}).+", self.parser.regex())
+    }
+
+    fn usage(&self) -> String {
+        self.usage_as(Source::NONE)
+    }
+
+    fn usage_as(&self, source: Source<'_>) -> String {
+        format!("{} <command>", self.parser.usage_as(source).trim_end())
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        self.nodes_as(Source::NONE)
+    }
+
+    fn nodes_as(&self, source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        self.parser.nodes_as(source)
+    }
+
+    fn permitted(&self, source: Source<'_>) -> bool {
+        self.parser.permitted(source)
+    }
+}
@@ -5,6 +5,9 @@ pub struct Product<H, T: HList>(pub(crate) H, pub(crate) T);
 pub trait HList: Sized {
     type Tuple: Tuple<HList = Self>;
 
+    /// The number of elements.
+    const LEN: usize;
+
     fn flatten(self) -> Self::Tuple;
 }
 
@@ -12,6 +15,8 @@ pub trait HList: Sized {
 pub trait Tuple: Sized {
     type HList: HList<Tuple = Self>;
 
+    const LEN: usize = <Self::HList as HList>::LEN;
+
     fn hlist(self) -> Self::HList;
 
     #[inline]
@@ -66,6 +71,7 @@ where
 
 impl HList for () {
     type Tuple = ();
+    const LEN: usize = 0;
     #[inline]
     fn flatten(self) -> Self::Tuple {}
 }
@@ -108,6 +114,7 @@ macro_rules! generics {
     ($type:ident) => {
         impl<$type> HList for Product!($type) {
             type Tuple = ($type,);
+            const LEN: usize = 1;
 
             #[inline]
             fn flatten(self) -> Self::Tuple {
@@ -156,6 +163,7 @@ macro_rules! generics {
 
         impl<$type1, $( $type ),*> HList for Product!($type1, $($type),*) {
             type Tuple = ($type1, $( $type ),*);
+            const LEN: usize = 1 + <Product!($( $type ),*) as HList>::LEN;
 
             #[inline]
             fn flatten(self) -> Self::Tuple {
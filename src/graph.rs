@@ -0,0 +1,505 @@
+//! Exports commands as a Brigadier node graph, which is what Minecraft clients expect in the
+//! Declare Commands packet to highlight and complete commands on their side.
+//!
+//! Every parser describes itself as the alternative sequences of nodes it could be, see [`IterParser::nodes`].
+//! The sequences of all commands are then merged into a single tree, sharing common prefixes.
+//!
+//! [`IterParser::nodes`]: crate::parser::IterParser::nodes
+
+use crate::command::Command;
+use crate::parser::Source;
+
+/// How a string argument is read by the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringKind {
+    /// A single word without spaces.
+    SingleWord = 0,
+    /// A single word, or a phrase in quotes.
+    QuotablePhrase = 1,
+    /// The rest of the input.
+    GreedyPhrase = 2,
+}
+
+/// The parsers of Brigadier that we send our arguments as. The bounds are both inclusive.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgumentParser {
+    Bool,
+    Integer {
+        min: Option<i32>,
+        max: Option<i32>,
+    },
+    Long {
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    Float {
+        min: Option<f32>,
+        max: Option<f32>,
+    },
+    Double {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    String(StringKind),
+    /// A player name, uuid or selector, that may be limited to a single entity or only players.
+    Entity {
+        single: bool,
+        players_only: bool,
+    },
+    IntRange,
+    FloatRange,
+    ResourceLocation,
+    NbtTag,
+    NbtCompoundTag,
+    Component,
+    BlockPos,
+    Vec3,
+    Vec2,
+    Rotation,
+}
+
+impl ArgumentParser {
+    /// The identifier of the parser in the protocol.
+    pub fn identifier(&self) -> &'static str {
+        match self {
+            ArgumentParser::Bool => "brigadier:bool",
+            ArgumentParser::Integer { .. } => "brigadier:integer",
+            ArgumentParser::Long { .. } => "brigadier:long",
+            ArgumentParser::Float { .. } => "brigadier:float",
+            ArgumentParser::Double { .. } => "brigadier:double",
+            ArgumentParser::String(_) => "brigadier:string",
+            ArgumentParser::Entity { .. } => "minecraft:entity",
+            ArgumentParser::IntRange => "minecraft:int_range",
+            ArgumentParser::FloatRange => "minecraft:float_range",
+            ArgumentParser::ResourceLocation => "minecraft:resource_location",
+            ArgumentParser::NbtTag => "minecraft:nbt_tag",
+            ArgumentParser::NbtCompoundTag => "minecraft:nbt_compound_tag",
+            ArgumentParser::Component => "minecraft:component",
+            ArgumentParser::BlockPos => "minecraft:block_pos",
+            ArgumentParser::Vec3 => "minecraft:vec3",
+            ArgumentParser::Vec2 => "minecraft:vec2",
+            ArgumentParser::Rotation => "minecraft:rotation",
+        }
+    }
+
+    /// Writes the properties that follow the identifier of the parser in the protocol.
+    pub fn write_properties(&self, buf: &mut Vec<u8>) {
+        fn flags<T>(min: &Option<T>, max: &Option<T>) -> u8 {
+            (min.is_some() as u8) | ((max.is_some() as u8) << 1)
+        }
+
+        match self {
+            ArgumentParser::Bool
+            | ArgumentParser::IntRange
+            | ArgumentParser::FloatRange
+            | ArgumentParser::ResourceLocation
+            | ArgumentParser::NbtTag
+            | ArgumentParser::NbtCompoundTag
+            | ArgumentParser::Component
+            | ArgumentParser::BlockPos
+            | ArgumentParser::Vec3
+            | ArgumentParser::Vec2
+            | ArgumentParser::Rotation => {}
+            ArgumentParser::Integer { min, max } => {
+                buf.push(flags(min, max));
+                min.iter()
+                    .chain(max)
+                    .for_each(|v| buf.extend(v.to_be_bytes()));
+            }
+            ArgumentParser::Long { min, max } => {
+                buf.push(flags(min, max));
+                min.iter()
+                    .chain(max)
+                    .for_each(|v| buf.extend(v.to_be_bytes()));
+            }
+            ArgumentParser::Float { min, max } => {
+                buf.push(flags(min, max));
+                min.iter()
+                    .chain(max)
+                    .for_each(|v| buf.extend(v.to_be_bytes()));
+            }
+            ArgumentParser::Double { min, max } => {
+                buf.push(flags(min, max));
+                min.iter()
+                    .chain(max)
+                    .for_each(|v| buf.extend(v.to_be_bytes()));
+            }
+            ArgumentParser::String(kind) => write_var_int(buf, *kind as i32),
+            ArgumentParser::Entity {
+                single,
+                players_only,
+            } => buf.push((*single as u8) | ((*players_only as u8) << 1)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeKind {
+    Root,
+    Literal(String),
+    Argument {
+        name: String,
+        parser: ArgumentParser,
+    },
+}
+
+impl NodeKind {
+    /// An argument node named after the usage of its parser, like `i32` for `<i32>`.
+    pub(crate) fn argument(usage: &str, parser: ArgumentParser) -> Self {
+        NodeKind::Argument {
+            name: usage
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string(),
+            parser,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub kind: NodeKind,
+    /// Whether the input is a complete command when it ends at this node.
+    pub executable: bool,
+    /// The indices of the child nodes in the graph.
+    pub children: Vec<usize>,
+    /// The index of the node parsing continues at after this one, instead of the children.
+    pub redirect: Option<usize>,
+}
+
+impl Node {
+    fn new(kind: NodeKind) -> Self {
+        Self {
+            kind,
+            executable: false,
+            children: Vec::new(),
+            redirect: None,
+        }
+    }
+}
+
+/// The node graph of a set of commands. The root node is always at index 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandGraph {
+    nodes: Vec<Node>,
+}
+
+impl Default for CommandGraph {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node::new(NodeKind::Root)],
+        }
+    }
+}
+
+impl CommandGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the graph of a command. For a [`Dispatcher`](crate::command::dispatcher::Dispatcher) this
+    /// includes all of the registered commands.
+    pub fn from_command<C: Command + ?Sized>(command: &C) -> Self {
+        Self::from_command_as(command, Source::NONE)
+    }
+
+    /// Creates the graph of the parts of a command that `source` is permitted to use, like the graph sent to a
+    /// player. See [`Require`](crate::parser::Require).
+    pub fn from_command_as<C: Command + ?Sized>(command: &C, source: Source<'_>) -> Self {
+        let mut graph = Self::new();
+        graph.add_command_as(command, source);
+        graph
+    }
+
+    pub fn add_command<C: Command + ?Sized>(&mut self, command: &C) {
+        self.add_command_as(command, Source::NONE)
+    }
+
+    pub fn add_command_as<C: Command + ?Sized>(&mut self, command: &C, source: Source<'_>) {
+        for path in command.nodes_as(source) {
+            self.add_path(path);
+        }
+        for path in command.redirects_as(source) {
+            self.add_redirect(path);
+        }
+    }
+
+    /// Adds the nodes below the root, reusing the nodes that are already there, and marks the last one as
+    /// executable. A leading `/` is removed from the first literal, since clients don't send it as part of the
+    /// command. Returns the index of the last node, or None if the path was empty.
+    pub fn add_path(&mut self, path: Vec<NodeKind>) -> Option<usize> {
+        let last = self.insert_path(path);
+        if let Some(last) = last {
+            self.nodes[last].executable = true;
+        }
+        last
+    }
+
+    /// Adds the nodes below the root like `add_path`, but the last one redirects to the root instead of being
+    /// executable, like `execute run`.
+    pub fn add_redirect(&mut self, path: Vec<NodeKind>) -> Option<usize> {
+        let last = self.insert_path(path);
+        if let Some(last) = last {
+            self.nodes[last].redirect = Some(0);
+        }
+        last
+    }
+
+    fn insert_path(&mut self, path: Vec<NodeKind>) -> Option<usize> {
+        let mut path = path.into_iter().peekable();
+
+        if let Some(NodeKind::Literal(first)) = path.peek_mut() {
+            *first = first.trim_start_matches('/').to_string();
+            if first.is_empty() {
+                path.next();
+            }
+        }
+
+        let mut parent = 0;
+        let mut last = None;
+        for kind in path {
+            let existing = self.nodes[parent]
+                .children
+                .iter()
+                .copied()
+                .find(|&child| self.nodes[child].kind == kind);
+
+            let node = match existing {
+                Some(node) => node,
+                None => {
+                    self.nodes.push(Node::new(kind));
+                    let node = self.nodes.len() - 1;
+                    self.nodes[parent].children.push(node);
+                    node
+                }
+            };
+            parent = node;
+            last = Some(node);
+        }
+        last
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    pub fn root(&self) -> &Node {
+        &self.nodes[0]
+    }
+
+    /// Finds the child of the node with the given literal or argument name.
+    pub fn child(&self, node: usize, name: &str) -> Option<usize> {
+        self.nodes[node]
+            .children
+            .iter()
+            .copied()
+            .find(|&child| match &self.nodes[child].kind {
+                NodeKind::Root => false,
+                NodeKind::Literal(literal) => literal == name,
+                NodeKind::Argument { name: argument, .. } => argument == name,
+            })
+    }
+
+    /// Writes the body of the Declare Commands packet, that is the nodes followed by the index of the root.
+    /// Parsers are written with their identifiers, like `brigadier:integer`, as in the protocol before 1.19.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        write_var_int(buf, self.nodes.len() as i32);
+
+        for node in &self.nodes {
+            let mut flags = match node.kind {
+                NodeKind::Root => 0,
+                NodeKind::Literal(_) => 1,
+                NodeKind::Argument { .. } => 2,
+            };
+            if node.executable {
+                flags |= 0x04;
+            }
+            if node.redirect.is_some() {
+                flags |= 0x08;
+            }
+            buf.push(flags);
+
+            write_var_int(buf, node.children.len() as i32);
+            for &child in &node.children {
+                write_var_int(buf, child as i32);
+            }
+            if let Some(redirect) = node.redirect {
+                write_var_int(buf, redirect as i32);
+            }
+
+            match &node.kind {
+                NodeKind::Root => {}
+                NodeKind::Literal(literal) => write_string(buf, literal),
+                NodeKind::Argument { name, parser } => {
+                    write_string(buf, name);
+                    write_string(buf, parser.identifier());
+                    parser.write_properties(buf);
+                }
+            }
+        }
+
+        write_var_int(buf, 0);
+    }
+}
+
+fn write_var_int(buf: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7f == 0 {
+            buf.push(value as u8);
+            return;
+        }
+        buf.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+fn write_string(buf: &mut Vec<u8>, string: &str) {
+    write_var_int(buf, string.len() as i32);
+    buf.extend(string.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::argument::QuotedString;
+    use crate::command::builder::{literal, CommandBuilder};
+    use crate::command::dispatcher::Dispatcher;
+    use crate::command::CommandSpec;
+
+    #[test]
+    fn var_int() {
+        let mut buf = Vec::new();
+        write_var_int(&mut buf, 0);
+        write_var_int(&mut buf, 300);
+        write_var_int(&mut buf, -1);
+        assert_eq!(buf, vec![0x00, 0xac, 0x02, 0xff, 0xff, 0xff, 0xff, 0x0f]);
+    }
+
+    #[test]
+    fn merges_commands() {
+        let mut dispatcher = Dispatcher::<(usize,), usize>::new();
+        dispatcher
+            .register(
+                literal("/tp")
+                    .space()
+                    .arg_named::<f64>("x")
+                    .opt_space()
+                    .opt_arg_named::<f64>("y")
+                    .on_call(|_: f64, _: Option<(f64,)>| move |_: usize| 0),
+            )
+            .unwrap();
+        dispatcher
+            .register(
+                literal("/tp")
+                    .space()
+                    .literal("spawn")
+                    .on_call(|| move |_: usize| 0),
+            )
+            .unwrap();
+        dispatcher
+            .register(
+                literal("/setblock")
+                    .space()
+                    .arg_range::<i32>(-64..=320)
+                    .space()
+                    .choice(vec![String::from("replace"), String::from("keep")])
+                    .on_call(|_: i32, _: String| move |_: usize| 0),
+            )
+            .unwrap();
+
+        let graph = CommandGraph::from_command(&dispatcher);
+        let nodes = graph.nodes();
+        assert_eq!(graph.root().children.len(), 2);
+
+        let tp = graph.child(0, "tp").unwrap();
+        assert_eq!(nodes[tp].kind, NodeKind::Literal(String::from("tp")));
+        assert!(!nodes[tp].executable);
+        assert_eq!(nodes[tp].children.len(), 2);
+
+        let x = graph.child(tp, "x").unwrap();
+        assert!(nodes[x].executable);
+        assert_eq!(
+            nodes[x].kind,
+            NodeKind::Argument {
+                name: String::from("x"),
+                parser: ArgumentParser::Double {
+                    min: None,
+                    max: None
+                }
+            }
+        );
+        let y = graph.child(x, "y").unwrap();
+        assert!(nodes[y].executable);
+        assert!(nodes[graph.child(tp, "spawn").unwrap()].executable);
+
+        let setblock = graph.child(0, "setblock").unwrap();
+        let y = graph.child(setblock, "i32").unwrap();
+        assert_eq!(
+            nodes[y].kind,
+            NodeKind::Argument {
+                name: String::from("i32"),
+                parser: ArgumentParser::Integer {
+                    min: Some(-64),
+                    max: Some(320)
+                }
+            }
+        );
+        assert!(!nodes[y].executable);
+        assert!(nodes[graph.child(y, "replace").unwrap()].executable);
+        assert!(nodes[graph.child(y, "keep").unwrap()].executable);
+    }
+
+    #[test]
+    fn argument_parsers() {
+        let command: CommandSpec<(usize,), usize, _, _, _> = literal("/msg")
+            .space()
+            .arg::<u64>()
+            .space()
+            .arg::<QuotedString>()
+            .on_call(|_: u64, _: QuotedString| move |_: usize| 0);
+        let graph = CommandGraph::from_command(&command);
+
+        let msg = graph.child(0, "msg").unwrap();
+        let number = graph.child(msg, "u64").unwrap();
+        assert_eq!(
+            graph.nodes()[number].kind,
+            NodeKind::Argument {
+                name: String::from("u64"),
+                parser: ArgumentParser::Long {
+                    min: Some(0),
+                    max: None
+                }
+            }
+        );
+        let string = graph.child(number, "\"string\"").unwrap();
+        assert!(matches!(
+            graph.nodes()[string].kind,
+            NodeKind::Argument {
+                parser: ArgumentParser::String(StringKind::QuotablePhrase),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn write() {
+        let command: CommandSpec<(usize,), usize, _, _, _> = literal("/kill")
+            .space()
+            .arg_range::<u8>(1..=10)
+            .on_call(|_: u8| move |_: usize| 0);
+        let mut buf = Vec::new();
+        CommandGraph::from_command(&command).write(&mut buf);
+
+        let mut expected = vec![3, 0x00, 1, 1];
+        expected.extend([0x01, 1, 2, 4]);
+        expected.extend(b"kill");
+        expected.extend([0x06, 0, 2]);
+        expected.extend(b"u8");
+        expected.push(17);
+        expected.extend(b"brigadier:integer");
+        expected.extend([0x03, 0, 0, 0, 1, 0, 0, 0, 10]);
+        expected.push(0);
+        assert_eq!(buf, expected);
+    }
+}
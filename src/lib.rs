@@ -1,9 +1,18 @@
+// Clippy suggests `Option::is_none_or` for `map_or(true, ..)`, but it needs a newer Rust than we want to require.
+#![allow(clippy::unnecessary_map_or)]
+
 pub mod argument;
 pub mod command;
 mod generic;
+pub mod graph;
 pub mod parser;
 pub mod regex;
 
+pub use lieutenant_derive::command;
+
+// Lets the code generated by the derive macros refer to `::lieutenant` inside this crate too.
+extern crate self as lieutenant;
+
 #[cfg(test)]
 extern crate quickcheck;
 #[cfg(test)]
@@ -62,4 +71,39 @@ mod tests {
             .expect("This should fill the second optional argument");
         assert!(x.call((0, "test"), "/test abc def").is_err());
     }
+
+    #[test]
+    fn command_macro() {
+        use crate::command;
+
+        /// Teleports the player.
+        #[command("/tp <x> <y> <z> [reason]")]
+        fn tp(player: usize, x: f32, y: f32, z: f32, reason: Option<String>) -> String {
+            match reason {
+                Some(reason) => format!("{} to {} {} {}: {}", player, x, y, z, reason),
+                None => return format!("{} to {} {} {}", player, x, y, z),
+            }
+        }
+
+        #[command("/spawn set")]
+        fn set_spawn(count: &mut u32) {
+            *count += 1;
+        }
+
+        let command = tp();
+        assert_eq!(
+            command.call((7,), "/tp 1 2 3.5 home").unwrap(),
+            "7 to 1 2 3.5: home"
+        );
+        assert_eq!(command.call((7,), "/tp 4 5 6").unwrap(), "7 to 4 5 6");
+        assert!(command.call((7,), "/tp 4 5").is_err());
+        assert_eq!(command.usage(), "/tp <x> <y> <z> [<reason>]");
+        assert_eq!(command.description(), Some("Teleports the player."));
+
+        let mut count = 0;
+        set_spawn().call((&mut count,), "/spawn set").unwrap();
+        set_spawn().call((&mut count,), "/spawn set").unwrap();
+        assert_eq!(count, 2);
+        assert!(set_spawn().call((&mut count,), "/spawn").is_err());
+    }
 }
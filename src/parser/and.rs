@@ -1,6 +1,7 @@
 use crate::generic::{Combine, CombinedTuples, Tuple};
+use crate::graph::NodeKind;
 
-use super::IterParser;
+use super::{ArgumentInfo, IterParser, ParseError, Source, Suggestion};
 
 pub struct And<A, B> {
     pub(crate) a: A,
@@ -74,12 +75,13 @@ where
     type ParserState = AndState<A, B>;
 
     #[allow(clippy::type_complexity, clippy::needless_return)]
-    fn parse<'p>(
+    fn parse_as<'p>(
         &self,
+        source: Source<'_>,
         state: AndState<A, B>,
         input: &'p str,
     ) -> (
-        anyhow::Result<(Self::Extract, &'p str)>,
+        Result<(Self::Extract, &'p str), ParseError>,
         Option<AndState<A, B>>,
     ) {
         let a_state = state.a_state;
@@ -95,7 +97,7 @@ where
 
                 let (_, a_out) = input.split_at(a_out_index);
 
-                let (b_ext, b_state) = self.b.parse(b_state, a_out);
+                let (b_ext, b_state) = self.b.parse_as(source, b_state, a_out);
 
                 match b_ext {
                     Ok((b_ext, b_out)) => {
@@ -104,9 +106,6 @@ where
                         match b_state {
                             Some(b_state) => {
                                 // The B parser has more to give. This leads us back to this match arm on the next call.
-
-                                let a_out_index = a_out.len();
-
                                 let cloned_a_ext = a_ext.clone();
                                 (
                                     Ok((a_ext.combine(b_ext), b_out)),
@@ -125,12 +124,12 @@ where
                         }
                     }
                     Err(err) => {
-                        // We did not have a match
+                        // We did not have a match. The error is relative to the input B was given.
+                        let err = err.shift(a_out_index, <A::Extract as Tuple>::LEN);
 
                         return match b_state {
                             Some(b_state) => {
                                 // More possible matches from the B parser. This leads us back to this match branch on the next call to parse.
-                                let a_out_index = a_out.len();
                                 (
                                     Err(err),
                                     Some(AndState {
@@ -153,7 +152,7 @@ where
                 // This is the entry point when calling parse with a fresh instance of Self::State::default(),
                 // This is also reachable from (Some, None, Some)
 
-                let (a_ext, a_state) = self.a.parse(a_state, input);
+                let (a_ext, a_state) = self.a.parse_as(source, a_state, input);
 
                 return match a_ext {
                     Err(err) => {
@@ -182,8 +181,9 @@ where
                         match a_state {
                             Some(a_state) => {
                                 // This leads us into  (Some, Some, Some)
-                                let a_out_index = a_out.len();
-                                self.parse(
+                                let a_out_index = input.len() - a_out.len();
+                                self.parse_as(
+                                    source,
                                     AndState {
                                         a_state: Some(a_state),
                                         a_ext: Some((a_ext, a_out_index)),
@@ -196,7 +196,8 @@ where
                                 // No more inputs are possible for A, leading us into (None, Some,Some)
 
                                 let a_out_index = input.len() - a_out.len();
-                                self.parse(
+                                self.parse_as(
+                                    source,
                                     AndState {
                                         a_state: None,
                                         a_ext: Some((a_ext, a_out_index)),
@@ -219,7 +220,8 @@ where
                 // Note: this is the branch you are working on.
                 //let a_state_clone = a_state.clone();
 
-                return self.parse(
+                return self.parse_as(
+                    source,
                     AndState {
                         a_state: Some(a_state),
                         a_ext: None,
@@ -235,14 +237,15 @@ where
                 // had a match for A, and then gone through all possible states for B. We now need to update our attempt
                 // at parsing with the first parser A.
 
-                let (a_ext, a_state) = self.a.parse(a_state, input);
+                let (a_ext, a_state) = self.a.parse_as(source, a_state, input);
 
                 return match (a_ext, a_state) {
                     (Ok((a_ext, a_out)), None) => {
                         // We got a new match and no more new ones are coming for a parser.
                         // this leads us to (None,Some,Some)
-                        let a_out_index = a_out.len();
-                        self.parse(
+                        let a_out_index = input.len() - a_out.len();
+                        self.parse_as(
+                            source,
                             AndState {
                                 a_state: None,
                                 a_ext: Some((a_ext, a_out_index)),
@@ -253,8 +256,9 @@ where
                     }
                     (Ok((a_ext, a_out)), Some(a_state)) => {
                         // This leads us into the Some, Some, Some branch
-                        let a_out_index = a_out.len();
-                        self.parse(
+                        let a_out_index = input.len() - a_out.len();
+                        self.parse_as(
+                            source,
                             AndState {
                                 a_state: Some(a_state),
                                 a_ext: Some((a_ext, a_out_index)),
@@ -287,7 +291,7 @@ where
 
                 // We continue trying different cases of the b_parser.
                 let (_, a_out) = input.split_at(a_out_index);
-                let (b_ext, b_state) = self.b.parse(b_state, a_out);
+                let (b_ext, b_state) = self.b.parse_as(source, b_state, a_out);
 
                 return match (b_ext, b_state) {
                     (Ok((b_ext, b_out)), None) => {
@@ -320,6 +324,7 @@ where
                         )
                     }
                     (Err(err), None) => {
+                        let err = err.shift(a_out_index, <A::Extract as Tuple>::LEN);
                         // We found nothing and there are no more to get for the B parser.
                         // We therefor reset the B state, and remove (a_ext, a_out).
                         // This moves us into the (Some, Some, None) branch
@@ -333,6 +338,7 @@ where
                         )
                     }
                     (Err(err), Some(b_state)) => {
+                        let err = err.shift(a_out_index, <A::Extract as Tuple>::LEN);
                         // We found nothing, but there are more to get from the B parser
                         // This sends us back to this branch on the next call to parse.
                         (
@@ -352,19 +358,91 @@ where
     fn regex(&self) -> String {
         format!("({})({})", &self.a.regex(), &self.b.regex())
     }
+
+    fn suggest_as(&self, source: Source<'_>, input: &str) -> Vec<Suggestion> {
+        let mut suggestions = self.a.suggest_as(source, input);
+
+        // Every way A can parse the input, leaves a different remainder for B to complete.
+        let mut a_state = Some(A::ParserState::default());
+        while let Some(state) = a_state {
+            let (a_ext, next_state) = self.a.parse_as(source, state, input);
+            if let Ok((_, a_out)) = a_ext {
+                let offset = input.len() - a_out.len();
+                // If A ends right at the end of the input, B would be glued onto the word A just parsed.
+                // That is almost never what is being typed, so we wait for a space before suggesting B.
+                let glued = a_out.is_empty() && offset > 0 && !input.ends_with(char::is_whitespace);
+                if !glued {
+                    for suggestion in self.b.suggest_as(source, a_out) {
+                        let suggestion = suggestion.shift(offset);
+                        if !suggestions.contains(&suggestion) {
+                            suggestions.push(suggestion);
+                        }
+                    }
+                }
+            }
+            a_state = next_state;
+        }
+
+        suggestions
+    }
+
+    fn usage_as(&self, source: Source<'_>) -> String {
+        format!("{}{}", self.a.usage_as(source), self.b.usage_as(source))
+    }
+
+    fn nodes_as(&self, source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        let b_nodes = self.b.nodes_as(source);
+        let mut nodes = Vec::new();
+        for a_path in self.a.nodes_as(source) {
+            for b_path in &b_nodes {
+                nodes.push(a_path.iter().chain(b_path).cloned().collect());
+            }
+        }
+        nodes
+    }
+
+    fn permitted(&self, source: Source<'_>) -> bool {
+        self.a.permitted(source) && self.b.permitted(source)
+    }
+
+    fn arguments(&self) -> Vec<&ArgumentInfo> {
+        let mut arguments = self.a.arguments();
+        arguments.extend(self.b.arguments());
+        arguments
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        self.parse_as(Source::NONE, state, input)
+    }
+
+    fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        self.suggest_as(Source::NONE, input)
+    }
+
+    fn usage(&self) -> String {
+        self.usage_as(Source::NONE)
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        self.nodes_as(Source::NONE)
+    }
 }
 
 mod tests {
 
     #[test]
     fn simple() {
-        let lit1 = crate::parser::Literal {
-            value: String::from("tp"),
-        };
+        let lit1 = crate::parser::Literal::new(String::from("tp"));
 
-        let lit2 = crate::parser::Literal {
-            value: String::from("me"),
-        };
+        let lit2 = crate::parser::Literal::new(String::from("me"));
 
         let and = crate::parser::And { a: lit1, b: lit2 };
 
@@ -383,13 +461,9 @@ mod tests {
     fn simple_opt_1() {
         let and = crate::parser::And {
             a: crate::parser::Opt {
-                parser: crate::parser::Literal {
-                    value: String::from("tp"),
-                },
-            },
-            b: crate::parser::Literal {
-                value: String::from("me"),
+                parser: crate::parser::Literal::new(String::from("tp")),
             },
+            b: crate::parser::Literal::new(String::from("me")),
         };
 
         let input = &mut "tp me";
@@ -403,15 +477,11 @@ mod tests {
 
     #[test]
     fn simple_opt_2() {
-        let lit1 = crate::parser::Literal {
-            value: String::from("tp"),
-        };
+        let lit1 = crate::parser::Literal::new(String::from("tp"));
 
         let opt1 = crate::parser::Opt { parser: lit1 };
 
-        let lit2 = crate::parser::Literal {
-            value: String::from("me"),
-        };
+        let lit2 = crate::parser::Literal::new(String::from("me"));
 
         let and = crate::parser::And { a: opt1, b: lit2 };
 
@@ -428,15 +498,11 @@ mod tests {
 
     #[test]
     fn simple_opt_3() {
-        let lit1 = crate::parser::Literal {
-            value: String::from("tp"),
-        };
+        let lit1 = crate::parser::Literal::new(String::from("tp"));
 
         let opt1 = crate::parser::Opt { parser: lit1 };
 
-        let lit2 = crate::parser::Literal {
-            value: String::from("me"),
-        };
+        let lit2 = crate::parser::Literal::new(String::from("me"));
 
         let opt2 = crate::parser::Opt { parser: lit2 };
 
@@ -457,15 +523,11 @@ mod tests {
     #[test]
     fn simple_opt_4() {
         for word in &["tp", "tango", "121", "œeœ", "ࢰࢰ", "😈😈😈"] {
-            let lit1 = crate::parser::Literal {
-                value: String::from(*word),
-            };
+            let lit1 = crate::parser::Literal::new(String::from(*word));
 
             let opt1 = crate::parser::Opt { parser: lit1 };
 
-            let lit2 = crate::parser::Literal {
-                value: String::from(*word),
-            };
+            let lit2 = crate::parser::Literal::new(String::from(*word));
 
             let opt2 = crate::parser::Opt { parser: lit2 };
 
@@ -482,4 +544,54 @@ mod tests {
             assert!(res.iter().all(|x| x.is_ok()));
         }
     }
+
+    #[test]
+    fn suggest_after_optional() {
+        use crate::parser::{IterParser, Suggestion};
+
+        let and = crate::parser::And {
+            a: crate::parser::Opt {
+                parser: crate::parser::Literal::new(String::from("tp")),
+            },
+            b: crate::parser::Literal::new(String::from("me")),
+        };
+
+        // Either the optional literal is being typed, or it was skipped and we are typing the second one.
+        assert_eq!(
+            and.suggest("t"),
+            vec![Suggestion::new(0..1, String::from("tp"))]
+        );
+        assert_eq!(
+            and.suggest("m"),
+            vec![Suggestion::new(0..1, String::from("me"))]
+        );
+        assert_eq!(
+            and.suggest("tp m"),
+            vec![Suggestion::new(3..4, String::from("me"))]
+        );
+        assert!(and.suggest("tp x").is_empty());
+    }
+
+    #[test]
+    fn suggest_waits_for_space() {
+        use crate::parser::{IterParser, Suggestion};
+
+        let and = crate::parser::And {
+            a: crate::parser::Literal::new(String::from("tp")),
+            b: crate::parser::And {
+                a: crate::parser::MaybeSpaces {},
+                b: crate::parser::Literal::new(String::from("me")),
+            },
+        };
+
+        // Right after "tp" the literal itself is being typed, so "me" is not glued onto it.
+        assert_eq!(
+            and.suggest("tp"),
+            vec![Suggestion::new(0..2, String::from("tp"))]
+        );
+        assert_eq!(
+            and.suggest("tp "),
+            vec![Suggestion::new(3..3, String::from("me"))]
+        );
+    }
 }
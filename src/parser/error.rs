@@ -0,0 +1,254 @@
+use std::{fmt, ops::Range};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The input did not start with the literal.
+    ExpectedLiteral(String),
+    ExpectedSpace,
+    InvalidNumber,
+    /// The number was outside of the bounds given to the argument, both inclusive.
+    OutOfRange {
+        min: String,
+        max: String,
+    },
+    /// Zero was given to an argument that only accepts nonzero numbers.
+    Zero,
+    InvalidBoolean,
+    UnknownChoice,
+    /// Fewer coordinates were given than the argument needs.
+    IncompleteCoordinates(usize),
+    /// Local coordinates with `^` were mixed with world coordinates.
+    MixedCoordinates,
+    /// Local coordinates with `^` were given to an argument that doesn't support them.
+    LocalCoordinatesNotAllowed,
+    /// The input is neither a player name, a uuid nor a selector.
+    InvalidEntity,
+    /// A selector started with `@`, but not with one of `@p`, `@r`, `@a`, `@e` or `@s`.
+    UnknownSelector,
+    UnknownFilter(String),
+    /// The value given to the filter of a selector is not valid for it.
+    InvalidFilterValue(String),
+    ExpectedCharacter(char),
+    /// A range was not like `1..5`, `..5`, `1..` or `3`, or its min was larger than its max.
+    InvalidRange,
+    /// A resource location had characters that are not allowed, like `Minecraft:stone` or `a:b:c`.
+    InvalidResourceLocation,
+    /// The resource location is valid, but not in the registry of the argument.
+    UnknownResource(String),
+    /// An SNBT compound had an entry without a key, like `{:1}`.
+    ExpectedNbtKey,
+    ExpectedNbtValue,
+    /// SNBT or JSON values were nested deeper than the limit.
+    NestedTooDeep(usize),
+    /// An element of an SNBT list or array did not have the same type as the others.
+    MixedNbtList,
+    /// An SNBT array like `[X;1]` had another type than `B`, `I` or `L`.
+    InvalidNbtArray(char),
+    ExpectedJsonValue,
+    /// A JSON number was not like `-1.5e3`, for example `05` or `1.`.
+    InvalidJsonNumber,
+    /// The JSON was valid, but not a chat component. The message describes what is wrong.
+    InvalidTextComponent(String),
+    /// A quoted string was not closed before the end of the input.
+    UnterminatedString,
+    /// A backslash in a quoted string was followed by a character that can't be escaped.
+    InvalidEscape(char),
+    /// A repeated argument had fewer values than its minimum.
+    TooFewElements(usize),
+    /// A flag like `--fast` or `-x` that the command does not have.
+    UnknownFlag(String),
+    /// An option was the last token, without a value.
+    MissingFlagValue(String),
+    /// A switch was given a value, like `--compress=yes`.
+    UnexpectedFlagValue(String),
+    /// The source of the command does not meet a requirement, see [`Require`](super::Require).
+    PermissionDenied,
+    /// The command was parsed, but there was more input left after it.
+    TooManyArguments,
+    /// The parsers accepted the whole input, but the regex of the command did not match it. This means one of the
+    /// parsers gives a regex that misses some of what it accepts.
+    RegexMismatch,
+    EmptyInput,
+    /// None of the commands in a dispatcher could be the start of the input.
+    UnknownCommand,
+    /// Used by parsers outside of this crate, that don't fit in any of the other kinds.
+    Custom(String),
+}
+
+/// The reason a parser failed, and where in the input it happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+
+    /// The bytes of the input that could not be parsed. When the error is returned from a parser this is relative
+    /// to the input the parser was given, but by the time it reaches the command it is relative to the whole input.
+    pub range: Range<usize>,
+
+    /// The position of the failing argument among the arguments given to the command closure.
+    /// This is None when the failure is in a literal or a space.
+    pub argument: Option<usize>,
+
+    /// The name of the failing argument, if it was added with a name.
+    pub argument_name: Option<String>,
+}
+
+impl ParseError {
+    pub fn new(kind: ParseErrorKind, range: Range<usize>) -> Self {
+        Self {
+            kind,
+            range,
+            argument: None,
+            argument_name: None,
+        }
+    }
+
+    /// Creates an error spanning the first word of the input, skipping leading whitespace.
+    pub fn at_token(kind: ParseErrorKind, input: &str) -> Self {
+        let token = input.trim_start();
+        let start = input.len() - token.len();
+        let end = token
+            .find(char::is_whitespace)
+            .map(|len| start + len)
+            .unwrap_or(input.len());
+        Self::new(kind, start..end)
+    }
+
+    /// Marks the error as coming from an argument. Parsers that extract a single value should use index 0,
+    /// since the index is adjusted by the parsers combining them.
+    pub fn in_argument(mut self, index: usize) -> Self {
+        self.argument = Some(index);
+        self
+    }
+
+    pub fn named(mut self, name: &str) -> Self {
+        self.argument_name = Some(String::from(name));
+        self
+    }
+
+    /// Moves the error when the input given to the parser started `offset` bytes into the input of the parent,
+    /// after `arguments` other arguments.
+    pub(crate) fn shift(mut self, offset: usize, arguments: usize) -> Self {
+        self.range = self.range.start + offset..self.range.end + offset;
+        self.argument = self.argument.map(|index| index + arguments);
+        self
+    }
+
+    /// Picks the error that got the furthest into the input. On a tie the first one is kept.
+    pub(crate) fn furthest(first: Option<Self>, second: Self) -> Self {
+        match first {
+            Some(first) if first.range.start >= second.range.start => first,
+            _ => second,
+        }
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::ExpectedLiteral(literal) => write!(f, "Expected '{}'", literal),
+            ParseErrorKind::ExpectedSpace => write!(f, "Expected a space"),
+            ParseErrorKind::InvalidNumber => write!(f, "Not a number"),
+            ParseErrorKind::OutOfRange { min, max } => {
+                write!(f, "Number must be between {} and {}", min, max)
+            }
+            ParseErrorKind::Zero => write!(f, "Number can't be zero"),
+            ParseErrorKind::InvalidBoolean => write!(f, "Expected 'true' or 'false'"),
+            ParseErrorKind::UnknownChoice => write!(f, "No choice matched"),
+            ParseErrorKind::IncompleteCoordinates(count) => {
+                write!(f, "Incomplete, expected {} coordinates", count)
+            }
+            ParseErrorKind::MixedCoordinates => {
+                write!(f, "Can't mix world and local coordinates")
+            }
+            ParseErrorKind::LocalCoordinatesNotAllowed => {
+                write!(f, "Local coordinates are not allowed here")
+            }
+            ParseErrorKind::InvalidEntity => write!(f, "Expected a player name, uuid or selector"),
+            ParseErrorKind::UnknownSelector => write!(f, "Unknown selector"),
+            ParseErrorKind::UnknownFilter(key) => write!(f, "Unknown filter '{}'", key),
+            ParseErrorKind::InvalidFilterValue(key) => {
+                write!(f, "Invalid value for filter '{}'", key)
+            }
+            ParseErrorKind::ExpectedCharacter(c) => write!(f, "Expected '{}'", c),
+            ParseErrorKind::InvalidRange => write!(f, "Invalid range"),
+            ParseErrorKind::InvalidResourceLocation => write!(f, "Invalid resource location"),
+            ParseErrorKind::UnknownResource(id) => write!(f, "Unknown resource '{}'", id),
+            ParseErrorKind::ExpectedNbtKey => write!(f, "Expected a key"),
+            ParseErrorKind::ExpectedNbtValue => write!(f, "Expected a value"),
+            ParseErrorKind::NestedTooDeep(max) => {
+                write!(f, "Data is nested deeper than {} levels", max)
+            }
+            ParseErrorKind::MixedNbtList => {
+                write!(f, "All elements of a list must have the same type")
+            }
+            ParseErrorKind::InvalidNbtArray(c) => write!(f, "Invalid array type '{}'", c),
+            ParseErrorKind::ExpectedJsonValue => write!(f, "Expected a JSON value"),
+            ParseErrorKind::InvalidJsonNumber => write!(f, "Invalid number"),
+            ParseErrorKind::InvalidTextComponent(message) => {
+                write!(f, "Invalid text component: {}", message)
+            }
+            ParseErrorKind::UnterminatedString => write!(f, "Unterminated quoted string"),
+            ParseErrorKind::InvalidEscape(c) => write!(f, "Invalid escape sequence '\\{}'", c),
+            ParseErrorKind::TooFewElements(min) => write!(f, "Expected at least {} values", min),
+            ParseErrorKind::UnknownFlag(flag) => write!(f, "Unknown flag '{}'", flag),
+            ParseErrorKind::MissingFlagValue(name) => {
+                write!(f, "Expected a value for '--{}'", name)
+            }
+            ParseErrorKind::UnexpectedFlagValue(name) => {
+                write!(f, "'--{}' does not take a value", name)
+            }
+            ParseErrorKind::PermissionDenied => {
+                write!(f, "You do not have permission to use this command")
+            }
+            ParseErrorKind::TooManyArguments => write!(f, "Too many arguments"),
+            ParseErrorKind::RegexMismatch => write!(f, "The command does not match its regex"),
+            ParseErrorKind::EmptyInput => write!(f, "Empty input"),
+            ParseErrorKind::UnknownCommand => write!(f, "Unknown command"),
+            ParseErrorKind::Custom(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {}..{}",
+            self.kind, self.range.start, self.range.end
+        )?;
+        match (&self.argument_name, self.argument) {
+            (Some(name), _) => write!(f, " (argument <{}>)", name)?,
+            (None, Some(argument)) => write!(f, " (argument {})", argument)?,
+            (None, None) => {}
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn at_token() {
+        let err = ParseError::at_token(ParseErrorKind::InvalidNumber, "  abc def");
+        assert_eq!(err.range, 2..5);
+
+        let err = ParseError::at_token(ParseErrorKind::EmptyInput, "  ");
+        assert_eq!(err.range, 2..2);
+    }
+
+    #[test]
+    fn furthest() {
+        let a = ParseError::new(ParseErrorKind::ExpectedSpace, 3..4);
+        let b = ParseError::new(ParseErrorKind::InvalidNumber, 5..6);
+        let c = ParseError::new(ParseErrorKind::UnknownChoice, 5..8);
+
+        let err = ParseError::furthest(None, a);
+        let err = ParseError::furthest(Some(err), b);
+        let err = ParseError::furthest(Some(err), c);
+        assert_eq!(err.kind, ParseErrorKind::InvalidNumber);
+    }
+}
@@ -1,5 +1,5 @@
 #[cfg(test)]
-use super::IterParser;
+use super::{IterParser, ParseError};
 
 /*
 This file contains  convenience struct that i use for testing.
@@ -20,7 +20,7 @@ impl<'p, P: IterParser> Evaluator<'p, P> {
     pub(crate) fn evaluate_all<'i>(
         &self,
         input: &'i str,
-    ) -> Vec<anyhow::Result<(P::Extract, &'i str)>> {
+    ) -> Vec<Result<(P::Extract, &'i str), ParseError>> {
         let mut result = Vec::new();
 
         let mut state = Some(P::ParserState::default());
@@ -0,0 +1,714 @@
+use std::{any::Any, fmt, mem, ops::Range, sync::Arc};
+
+use indexmap::IndexMap;
+use regex_syntax::ast::{self, Ast};
+
+use crate::argument::Argument;
+use crate::generic::{Combine, CombinedTuples, Tuple};
+use crate::graph::{ArgumentParser, NodeKind, StringKind};
+
+use super::{ArgumentInfo, IterParser, ParseError, ParseErrorKind, Source, Suggestion};
+
+type Value = Arc<dyn Any + Send + Sync>;
+type ParseValue = Box<dyn Fn(&str) -> Result<Value, ParseError>>;
+
+enum FlagKind {
+    Switch,
+    Option {
+        usage: String,
+        parse: ParseValue,
+        default: Option<Value>,
+    },
+}
+
+struct Flag {
+    name: String,
+    short: Option<char>,
+    kind: FlagKind,
+}
+
+impl Flag {
+    fn usage(&self) -> String {
+        let long = match &self.kind {
+            FlagKind::Switch => format!("--{}", self.name),
+            FlagKind::Option { usage, .. } => format!("--{}={}", self.name, usage),
+        };
+        match self.short {
+            Some(short) => format!("[-{}|{}]", short, long),
+            None => format!("[{}]", long),
+        }
+    }
+}
+
+/// The flags a command accepts, like `--compress`, `--level=9` or `-v`. Add them to a command with
+/// [`CommandBuilder::flags`](crate::command::builder::CommandBuilder::flags).
+#[derive(Default)]
+pub struct FlagSet {
+    flags: Vec<Flag>,
+}
+
+impl FlagSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a flag without a value, like `--compress`.
+    pub fn switch(mut self, name: &str) -> Self {
+        self.flags.push(Flag {
+            name: String::from(name),
+            short: None,
+            kind: FlagKind::Switch,
+        });
+        self
+    }
+
+    /// Adds a flag with a value parsed as `A`, written `--name=value` or `--name value`.
+    pub fn option<A>(self, name: &str) -> Self
+    where
+        A: Argument + Send + Sync + 'static,
+    {
+        self.add_option::<A>(name, None)
+    }
+
+    /// Like `option`, but the value is `default` when the flag is not given.
+    pub fn option_default<A>(self, name: &str, default: A) -> Self
+    where
+        A: Argument + Send + Sync + 'static,
+    {
+        self.add_option::<A>(name, Some(Arc::new(default)))
+    }
+
+    fn add_option<A>(mut self, name: &str, default: Option<Value>) -> Self
+    where
+        A: Argument + Send + Sync + 'static,
+    {
+        let parser = A::Parser::default();
+        let usage = parser.usage();
+        let parse = move |input: &str| {
+            let mut state = Some(A::ParserState::default());
+            let mut error = None;
+            while let Some(current) = state {
+                let (result, next) = parser.parse(current, input);
+                match result {
+                    Ok(((value,), "")) => return Ok(Arc::new(value) as Value),
+                    Ok((_, rest)) => {
+                        let start = input.len() - rest.len();
+                        let err =
+                            ParseError::new(ParseErrorKind::TooManyArguments, start..input.len());
+                        error = Some(ParseError::furthest(error, err));
+                    }
+                    Err(err) => error = Some(ParseError::furthest(error, err)),
+                }
+                state = next;
+            }
+            Err(error.expect("the parser gave no result"))
+        };
+
+        self.flags.push(Flag {
+            name: String::from(name),
+            short: None,
+            kind: FlagKind::Option {
+                usage,
+                parse: Box::new(parse),
+                default,
+            },
+        });
+        self
+    }
+
+    /// Gives the last added flag a short alias, like `-v` for `--verbose`. Short switches can be grouped, like
+    /// `-vq`.
+    pub fn short(mut self, short: char) -> Self {
+        if let Some(flag) = self.flags.last_mut() {
+            flag.short = Some(short);
+        }
+        self
+    }
+
+    fn long(&self, name: &str) -> Option<&Flag> {
+        self.flags.iter().find(|flag| flag.name == name)
+    }
+
+    fn by_short(&self, short: char) -> Option<&Flag> {
+        self.flags.iter().find(|flag| flag.short == Some(short))
+    }
+}
+
+/// The values of the flags given to a command. Options with a default always have a value.
+#[derive(Clone, Default)]
+pub struct FlagValues {
+    values: IndexMap<String, Value>,
+}
+
+impl FlagValues {
+    /// Whether the switch or option was given, or has a default.
+    pub fn is_set(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    /// The value of an option, which must have the type it was declared with. Switches have the value `true`.
+    pub fn get<T: Clone + 'static>(&self, name: &str) -> Option<T> {
+        self.values.get(name)?.downcast_ref::<T>().cloned()
+    }
+}
+
+impl fmt::Debug for FlagValues {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.values.keys()).finish()
+    }
+}
+
+/// Splits the input at whitespace, keeping quoted strings in one token.
+fn tokens(input: &str) -> Vec<Range<usize>> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in input.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c.is_whitespace() => {
+                if let Some(start) = start.take() {
+                    tokens.push(start..i);
+                }
+                continue;
+            }
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None => {}
+        }
+        start.get_or_insert(i);
+    }
+    if let Some(start) = start {
+        tokens.push(start..input.len());
+    }
+    tokens
+}
+
+/// Matches a flag with an optional value, after some whitespace. Like in [`tokens`], quoted parts of a token
+/// may contain whitespace.
+fn flag_regex() -> String {
+    let part = r#"(?:[^\s"']|"(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*')"#;
+    format!(r"(?:\s+-{part}*(?:\s+{part}+)?)*", part = part)
+}
+
+/// Puts `flag` in front of every `\s+` and `\s*` of the regex, which are the places the parser allows spaces.
+fn allow_flags(regex: &mut Ast, flag: &Ast) {
+    match regex {
+        Ast::Repetition(repetition) if is_spaces(repetition) => {
+            let span = repetition.span;
+            let spaces = mem::replace(regex, Ast::Empty(span));
+            *regex = Ast::Group(ast::Group {
+                span,
+                kind: ast::GroupKind::NonCapturing(ast::Flags {
+                    span,
+                    items: Vec::new(),
+                }),
+                ast: Box::new(Ast::Concat(ast::Concat {
+                    span,
+                    asts: vec![flag.clone(), spaces],
+                })),
+            });
+        }
+        Ast::Repetition(repetition) => allow_flags(&mut repetition.ast, flag),
+        Ast::Group(group) => allow_flags(&mut group.ast, flag),
+        Ast::Alternation(alternation) => {
+            for regex in &mut alternation.asts {
+                allow_flags(regex, flag);
+            }
+        }
+        Ast::Concat(concat) => {
+            for regex in &mut concat.asts {
+                allow_flags(regex, flag);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_spaces(repetition: &ast::Repetition) -> bool {
+    let repeated = matches!(
+        repetition.op.kind,
+        ast::RepetitionKind::ZeroOrMore | ast::RepetitionKind::OneOrMore
+    );
+    let space = matches!(
+        &*repetition.ast,
+        Ast::Class(ast::Class::Perl(ast::ClassPerl {
+            kind: ast::ClassPerlKind::Space,
+            negated: false,
+            ..
+        }))
+    );
+    repeated && space
+}
+
+/// The input without its flags, and where its parts were in the original input.
+struct Stripped {
+    input: String,
+    /// The start of each part in the stripped input and in the original input.
+    parts: Vec<(usize, usize)>,
+    original_len: usize,
+}
+
+impl Stripped {
+    /// Finds the position in the original input of a position in the stripped input.
+    fn original(&self, pos: usize) -> usize {
+        // The end stays the end, even if flags were removed from it.
+        if pos >= self.input.len() {
+            return self.original_len;
+        }
+        let part = self.parts.iter().rev().find(|(start, _)| *start <= pos);
+        match part {
+            Some((start, original)) => (original + pos - start).min(self.original_len),
+            None => pos,
+        }
+    }
+
+    fn original_range(&self, range: Range<usize>) -> Range<usize> {
+        self.original(range.start)..self.original(range.end).max(self.original(range.start))
+    }
+
+    fn push(&mut self, original: &str, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        self.parts.push((self.input.len(), range.start));
+        self.input.push_str(&original[range]);
+    }
+}
+
+/// A parser that accepts flags anywhere among the arguments of another parser. The flags are removed from the
+/// input before the other parser sees it, and everything after a `--` is never a flag. A `-` followed by a
+/// digit is a negative number, not a flag.
+pub struct WithFlags<P> {
+    pub(crate) parser: P,
+    pub(crate) flags: FlagSet,
+}
+
+impl<P: IterParser> WithFlags<P> {
+    /// Removes the flags from the input and parses their values. The errors have the argument index of the
+    /// flag values.
+    fn strip(&self, input: &str) -> Result<(Stripped, FlagValues), ParseError> {
+        let argument = <P::Extract as Tuple>::LEN;
+        let error = |kind, range| ParseError::new(kind, range).in_argument(argument);
+
+        let mut stripped = Stripped {
+            input: String::new(),
+            parts: Vec::new(),
+            original_len: input.len(),
+        };
+        let mut values = FlagValues::default();
+        let mut set = |flag: &Flag, value: Option<(&str, usize)>| -> Result<(), ParseError> {
+            let value = match (&flag.kind, value) {
+                (FlagKind::Switch, _) => Arc::new(true) as Value,
+                (FlagKind::Option { parse, .. }, Some((value, start))) => {
+                    parse(value).map_err(|err| err.shift(start, 0).in_argument(argument))?
+                }
+                (FlagKind::Option { .. }, None) => unreachable!("options always get a value"),
+            };
+            values.values.insert(flag.name.clone(), value);
+            Ok(())
+        };
+
+        let tokens = tokens(input);
+        let mut end = 0;
+        let mut flags_ended = false;
+        let mut i = 0;
+        while i < tokens.len() {
+            let range = tokens[i].clone();
+            let token = &input[range.clone()];
+            i += 1;
+
+            // The value of an option given as the next token.
+            let mut next_value = |name: &str| match tokens.get(i) {
+                Some(next) => {
+                    i += 1;
+                    Ok((&input[next.clone()], next.start))
+                }
+                None => Err(error(
+                    ParseErrorKind::MissingFlagValue(String::from(name)),
+                    range.clone(),
+                )),
+            };
+
+            if flags_ended || !token.starts_with('-') || token.len() == 1 {
+                stripped.push(input, end..range.end);
+            } else if token == "--" {
+                flags_ended = true;
+            } else if let Some(long) = token.strip_prefix("--") {
+                let (name, value) = match long.split_once('=') {
+                    Some((name, value)) => (name, Some((value, range.start + 3 + name.len()))),
+                    None => (long, None),
+                };
+                let flag = match self.flags.long(name) {
+                    Some(flag) => flag,
+                    None => {
+                        return Err(error(
+                            ParseErrorKind::UnknownFlag(String::from(token)),
+                            range,
+                        ))
+                    }
+                };
+                match (&flag.kind, value) {
+                    (FlagKind::Switch, Some(_)) => {
+                        return Err(error(
+                            ParseErrorKind::UnexpectedFlagValue(String::from(name)),
+                            range,
+                        ))
+                    }
+                    (FlagKind::Switch, None) => set(flag, None)?,
+                    (_, Some(value)) => set(flag, Some(value))?,
+                    (_, None) => set(flag, Some(next_value(name)?))?,
+                }
+                end = tokens[i - 1].end;
+                continue;
+            } else if token[1..].starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+                stripped.push(input, end..range.end);
+            } else {
+                // A group of short flags, where an option takes the rest of the group or the next token as value.
+                for (j, short) in token.char_indices().skip(1) {
+                    let flag = match self.flags.by_short(short) {
+                        Some(flag) => flag,
+                        None => {
+                            let at = range.start + j;
+                            return Err(error(
+                                ParseErrorKind::UnknownFlag(format!("-{}", short)),
+                                at..at + short.len_utf8(),
+                            ));
+                        }
+                    };
+                    if let FlagKind::Switch = flag.kind {
+                        set(flag, None)?;
+                        continue;
+                    }
+                    let rest = j + short.len_utf8();
+                    if rest < token.len() {
+                        set(flag, Some((&token[rest..], range.start + rest)))?;
+                    } else {
+                        set(flag, Some(next_value(&flag.name)?))?;
+                    }
+                    break;
+                }
+                end = tokens[i - 1].end;
+                continue;
+            }
+            end = range.end;
+        }
+        stripped.push(input, end..input.len());
+
+        for flag in &self.flags.flags {
+            if let FlagKind::Option {
+                default: Some(default),
+                ..
+            } = &flag.kind
+            {
+                values
+                    .values
+                    .entry(flag.name.clone())
+                    .or_insert_with(|| default.clone());
+            }
+        }
+
+        Ok((stripped, values))
+    }
+}
+
+impl<P> IterParser for WithFlags<P>
+where
+    P: IterParser,
+    <P::Extract as Tuple>::HList: Combine<<(FlagValues,) as Tuple>::HList>,
+{
+    type Extract = CombinedTuples<P::Extract, (FlagValues,)>;
+    type ParserState = P::ParserState;
+
+    #[allow(clippy::type_complexity)]
+    fn parse_as<'p>(
+        &self,
+        source: Source<'_>,
+        state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        let (stripped, values) = match self.strip(input) {
+            Ok(stripped) => stripped,
+            Err(err) => return (Err(err), None),
+        };
+
+        let (result, next) = self.parser.parse_as(source, state, &stripped.input);
+        let result = match result {
+            Ok((ext, rest)) => {
+                let end = stripped.original(stripped.input.len() - rest.len());
+                Ok((ext.combine((values,)), &input[end..]))
+            }
+            Err(mut err) => {
+                err.range = stripped.original_range(err.range);
+                Err(err)
+            }
+        };
+        (result, next)
+    }
+
+    /// Lets flags, with an optional value, appear wherever the parser allows spaces and at the end.
+    fn regex(&self) -> String {
+        let flag = flag_regex();
+        let regex = self.parser.regex();
+        let parse = |regex: &str| ast::parse::Parser::new().parse(regex).ok();
+        match (parse(&regex), parse(&flag)) {
+            (Some(mut regex), Some(flag_ast)) => {
+                allow_flags(&mut regex, &flag_ast);
+                format!("{}{}", regex, flag)
+            }
+            // An invalid regex stays invalid, so it is reported when the command is built.
+            _ => format!("{}{}", regex, flag),
+        }
+    }
+
+    fn suggest_as(&self, source: Source<'_>, input: &str) -> Vec<Suggestion> {
+        let word_start = input.rfind(char::is_whitespace).map_or(0, |i| {
+            i + input[i..].chars().next().map_or(1, char::len_utf8)
+        });
+        let word = &input[word_start..];
+
+        if word.starts_with('-') && word_start > 0 {
+            return self
+                .flags
+                .flags
+                .iter()
+                .map(|flag| format!("--{}", flag.name))
+                .filter(|flag| flag.starts_with(word))
+                .map(|flag| Suggestion::new(word_start..input.len(), flag))
+                .collect();
+        }
+
+        match self.strip(input) {
+            Ok((stripped, _)) => self
+                .parser
+                .suggest_as(source, &stripped.input)
+                .into_iter()
+                .map(|mut suggestion| {
+                    suggestion.range = stripped.original_range(suggestion.range);
+                    suggestion
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn usage_as(&self, source: Source<'_>) -> String {
+        let flags: Vec<String> = self.flags.flags.iter().map(Flag::usage).collect();
+        format!(
+            "{} {}",
+            self.parser.usage_as(source).trim_end(),
+            flags.join(" ")
+        )
+    }
+
+    /// Brigadier can't place flags among the arguments, so they are exported as a greedy string at the end.
+    fn nodes_as(&self, source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        let flags = NodeKind::argument("flags", ArgumentParser::String(StringKind::GreedyPhrase));
+        self.parser
+            .nodes_as(source)
+            .into_iter()
+            .flat_map(|path| {
+                let mut with_flags = path.clone();
+                with_flags.push(flags.clone());
+                vec![path, with_flags]
+            })
+            .collect()
+    }
+
+    fn permitted(&self, source: Source<'_>) -> bool {
+        self.parser.permitted(source)
+    }
+
+    fn arguments(&self) -> Vec<&ArgumentInfo> {
+        self.parser.arguments()
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        self.parse_as(Source::NONE, state, input)
+    }
+
+    fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        self.suggest_as(Source::NONE, input)
+    }
+
+    fn usage(&self) -> String {
+        self.usage_as(Source::NONE)
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        self.nodes_as(Source::NONE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::argument::QuotedString;
+    use crate::command::builder::{literal, CommandBuilder};
+    use crate::command::{Command, CommandSpec};
+    use crate::regex::NFA;
+
+    fn backup() -> impl Command<GameState = (), CommandResult = (String, bool, u32, bool)> {
+        let command: CommandSpec<(), (String, bool, u32, bool), _, _, _> = literal("/backup")
+            .space()
+            .literal("create")
+            .space()
+            .arg::<String>()
+            .flags(
+                FlagSet::new()
+                    .switch("compress")
+                    .short('c')
+                    .option_default::<u32>("level", 6)
+                    .short('l')
+                    .switch("verbose")
+                    .short('v'),
+            )
+            .on_call(|name: String, flags: FlagValues| {
+                move || {
+                    (
+                        name.clone(),
+                        flags.is_set("compress"),
+                        flags.get::<u32>("level").unwrap(),
+                        flags.is_set("verbose"),
+                    )
+                }
+            });
+        command
+    }
+
+    #[test]
+    fn flags() {
+        let command = backup();
+        let call = |input: &str| command.call((), input);
+
+        assert_eq!(
+            call("/backup create --compress --level=9 -v name").unwrap(),
+            (String::from("name"), true, 9, true)
+        );
+        assert_eq!(
+            call("/backup create name").unwrap(),
+            (String::from("name"), false, 6, false)
+        );
+        assert_eq!(
+            call("/backup --level 3 create name -cv").unwrap(),
+            (String::from("name"), true, 3, true)
+        );
+        assert_eq!(
+            call("/backup create -l2 -- -name").unwrap(),
+            (String::from("-name"), false, 2, false)
+        );
+        assert_eq!(
+            call("/backup create -5").unwrap(),
+            (String::from("-5"), false, 6, false)
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let command = backup();
+        let call = |input: &str| command.call((), input).unwrap_err();
+
+        let err = call("/backup create --fast name");
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::UnknownFlag(String::from("--fast"))
+        );
+        assert_eq!(err.range, 15..21);
+        assert_eq!(err.argument, Some(1));
+
+        let err = call("/backup create -cx name");
+        assert_eq!(err.kind, ParseErrorKind::UnknownFlag(String::from("-x")));
+        assert_eq!(err.range, 17..18);
+
+        let err = call("/backup create name --level");
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::MissingFlagValue(String::from("level"))
+        );
+
+        let err = call("/backup create --level=high name");
+        assert_eq!(err.kind, ParseErrorKind::InvalidNumber);
+        assert_eq!(err.range, 23..27);
+
+        let err = call("/backup create --compress=yes name");
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::UnexpectedFlagValue(String::from("compress"))
+        );
+
+        // Errors of the other arguments point at the original input.
+        let err = call("/backup -v crate name");
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::ExpectedLiteral(String::from("create"))
+        );
+        assert_eq!(err.range, 11..16);
+    }
+
+    #[test]
+    fn usage_and_suggestions() {
+        let command = backup();
+        assert_eq!(
+            command.usage(),
+            "/backup create <string> [-c|--compress] [-l|--level=<u32>] [-v|--verbose]"
+        );
+        assert_eq!(
+            command.suggest("/backup create name --c", 23),
+            vec![Suggestion::new(20..23, String::from("--compress"))]
+        );
+        assert_eq!(
+            command.suggest("/backup -v cr", 13),
+            vec![Suggestion::new(11..13, String::from("create"))]
+        );
+        assert!(NFA::<usize>::regex(&command.regex()).is_ok());
+    }
+
+    #[test]
+    fn quoted_values() {
+        let command: CommandSpec<(), String, _, _, _> = literal("/backup")
+            .space()
+            .arg::<String>()
+            .flags(FlagSet::new().option::<QuotedString>("name"))
+            .on_call(|_: String, flags: FlagValues| {
+                move || flags.get::<QuotedString>("name").unwrap().get().to_string()
+            });
+
+        assert_eq!(
+            command.call((), r#"/backup x --name "a b""#).unwrap(),
+            "a b"
+        );
+        assert_eq!(
+            command.call((), r#"/backup --name='c d' x"#).unwrap(),
+            "c d"
+        );
+    }
+
+    #[test]
+    fn regex() {
+        let rewrite = |regex: &str| {
+            let parse = |regex: &str| ast::parse::Parser::new().parse(regex).unwrap();
+            let mut regex = parse(regex);
+            allow_flags(&mut regex, &parse("F"));
+            regex.to_string()
+        };
+
+        assert_eq!(rewrite(r"a\s+b"), r"a(?:F\s+)b");
+        assert_eq!(rewrite(r"(x|\s*)y"), r"(x|(?:F\s*))y");
+        // Escaped backslashes and other classes are left alone.
+        assert_eq!(rewrite(r"a\\s+b\S+"), r"a\\s+b\S+");
+    }
+}
@@ -1,14 +1,72 @@
-use super::IterParser;
-use anyhow::{anyhow, Result};
+use super::{IterParser, ParseError, ParseErrorKind, Suggestion};
+use crate::graph::NodeKind;
 
 /// A literal should not have leading or trailing whitespaces.
 pub struct Literal {
     pub(crate) value: String,
+    /// Other names the literal is accepted under, like `/teleport` for `/tp`.
+    pub(crate) aliases: Vec<String>,
 }
 
 impl Literal {
     pub fn new(value: String) -> Self {
-        Self { value }
+        Self {
+            value,
+            aliases: Vec::new(),
+        }
+    }
+
+    /// Also accepts the literal under another name. Everything after the literal is shared, so
+    /// `literal("/gamemode").alias("/gm")` gives both names the same arguments.
+    pub fn alias(mut self, alias: &str) -> Self {
+        self.aliases.push(String::from(alias));
+        self
+    }
+
+    fn names(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.value).chain(&self.aliases)
+    }
+}
+
+/// Returns the length of the name at the start of the input, after any whitespace, if it is followed by a space
+/// or the end of the input. The name is matched case-insensitively.
+fn match_name(name: &str, input: &str) -> Option<usize> {
+    let input_lower = &mut input
+        .trim_start()
+        .chars()
+        .flat_map(|c| c.to_lowercase())
+        .peekable();
+    let literal_lower = &mut name.chars().flat_map(|c| c.to_lowercase()).peekable();
+    // Start after the whitespace we skipped.
+    let mut offset = input.len() - input.trim_start().len();
+
+    loop {
+        match (literal_lower.next(), input_lower.next()) {
+            (None, None) => {
+                // Then the two str had the same length and were identical up to that point
+                return Some(input.len());
+            }
+            (None, Some(' ')) => {
+                // We have reached the end of the literal, and the next input char is a space
+                return Some(offset);
+            }
+            (None, Some(_)) => {
+                // We have reached the end of the literal, and the next input char is not a space.
+                return None;
+            }
+            (Some(_), None) => {
+                // The input is shorter than the literal.
+                return None;
+            }
+            (Some(lit_c), Some(inp_c)) => {
+                if lit_c == inp_c {
+                    offset += lit_c.len_utf8();
+                    continue;
+                } else {
+                    return None;
+                }
+            }
+        }
     }
 }
 
@@ -21,59 +79,52 @@ impl IterParser for Literal {
         &self,
         _state: Self::ParserState,
         input: &'i str,
-    ) -> (Result<(Self::Extract, &'i str)>, Option<Self::ParserState>) {
-        let input_lower = &mut input
-            .trim_start()
-            .chars()
-            .flat_map(|c| c.to_lowercase())
-            .peekable();
-        let literal_lower = &mut self.value.chars().flat_map(|c| c.to_lowercase()).peekable();
-        let mut offset = 0;
-
-        loop {
-            match (literal_lower.next(), input_lower.next()) {
-                (None, None) => {
-                    // Then the two str had the same length and were identical up to that point
-                    return (Ok(((), "")), None);
-                }
-                (None, Some(' ')) => {
-                    // We have reached the end of the literal, and the next input char is a space
-                    return (Ok(((), &input[offset..])), None);
-                }
-                (None, Some(c)) => {
-                    // We have reached the end of the literal, and the next input char is not a space.
-                    return (
-                        Err(anyhow!(
-                            "Next char:'{}' was not whitespace or 'end of str' after literal.",
-                            c
-                        )),
-                        None,
-                    );
-                }
-                (Some(_), None) => {
-                    return (
-                        Err(anyhow!(
-                            "The input: \"{}\" is shorter then the literal: \"{}\"",
-                            &input,
-                            self.value.as_str()
-                        )),
-                        None,
-                    );
-                }
-                (Some(lit_c), Some(inp_c)) => {
-                    if lit_c == inp_c {
-                        offset += lit_c.len_utf8();
-                        continue;
-                    } else {
-                        return (Err(anyhow!("Literal did not match input")), None);
-                    }
-                }
-            }
+    ) -> (
+        Result<(Self::Extract, &'i str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        match self.names().find_map(|name| match_name(name, input)) {
+            Some(end) => (Ok(((), &input[end..])), None),
+            None => (
+                Err(ParseError::at_token(
+                    ParseErrorKind::ExpectedLiteral(self.value.clone()),
+                    input,
+                )),
+                None,
+            ),
         }
     }
 
     fn regex(&self) -> String {
-        regex_syntax::escape(self.value.as_str())
+        if self.aliases.is_empty() {
+            return regex_syntax::escape(self.value.as_str());
+        }
+        let names: Vec<String> = self
+            .names()
+            .map(|name| regex_syntax::escape(name))
+            .collect();
+        format!("({})", names.join("|"))
+    }
+
+    fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        self.names()
+            .filter_map(|name| Suggestion::complete_word(input, name))
+            .collect()
+    }
+
+    fn usage(&self) -> String {
+        if self.aliases.is_empty() {
+            return self.value.clone();
+        }
+        let names: Vec<&str> = self.names().map(String::as_str).collect();
+        format!("({})", names.join("|"))
+    }
+
+    /// Every name gets its own node, followed by the same nodes.
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        self.names()
+            .map(|name| vec![NodeKind::Literal(name.clone())])
+            .collect()
     }
 }
 
@@ -85,9 +136,7 @@ mod tests {
 
     #[test]
     fn simple() {
-        let lit = Literal {
-            value: String::from("tp"),
-        };
+        let lit = Literal::new(String::from("tp"));
 
         let input = &mut "tp 10 10 10";
 
@@ -101,9 +150,7 @@ mod tests {
 
     #[test]
     fn empty() {
-        let lit = Literal {
-            value: String::from("tp"),
-        };
+        let lit = Literal::new(String::from("tp"));
 
         let input = "";
 
@@ -116,9 +163,7 @@ mod tests {
 
     #[test]
     fn partial() {
-        let lit = Literal {
-            value: String::from("tp"),
-        };
+        let lit = Literal::new(String::from("tp"));
 
         let input = "tpme";
 
@@ -132,9 +177,7 @@ mod tests {
 
     #[test]
     fn case() {
-        let lit = Literal {
-            value: String::from("tp"),
-        };
+        let lit = Literal::new(String::from("tp"));
 
         let input = "tp me";
 
@@ -143,4 +186,24 @@ mod tests {
         assert_eq!(res.len(), 1);
         assert_eq!(res.get(0).unwrap().as_ref().unwrap().1, " me");
     }
+
+    #[test]
+    fn suggest() {
+        let lit = Literal::new(String::from("teleport"));
+
+        assert_eq!(
+            lit.suggest("tel"),
+            vec![Suggestion::new(0..3, String::from("teleport"))]
+        );
+        assert_eq!(
+            lit.suggest("  TE"),
+            vec![Suggestion::new(2..4, String::from("teleport"))]
+        );
+        assert_eq!(
+            lit.suggest(""),
+            vec![Suggestion::new(0..0, String::from("teleport"))]
+        );
+        assert!(lit.suggest("tp").is_empty());
+        assert!(lit.suggest("tel me").is_empty());
+    }
 }
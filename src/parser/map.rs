@@ -1,5 +1,6 @@
 use crate::generic::Func;
-use crate::parser::IterParser;
+use crate::graph::NodeKind;
+use crate::parser::{ArgumentInfo, IterParser, ParseError, Source, Suggestion};
 
 pub struct Map<P, F> {
     pub(crate) parser: P,
@@ -28,22 +29,71 @@ where
     type ParserState = P::ParserState;
 
     #[allow(clippy::type_complexity)]
-    fn parse<'p>(
+    fn parse_as<'p>(
         &self,
+        source: Source<'_>,
         state: Self::ParserState,
         input: &'p str,
     ) -> (
-        anyhow::Result<(Self::Extract, &'p str), anyhow::Error>,
+        Result<(Self::Extract, &'p str), ParseError>,
         Option<Self::ParserState>,
     ) {
-        let (result, state) = self.parser.parse(state, input);
+        let (result, state) = self.parser.parse_as(source, state, input);
 
         match result {
             Ok((ext, out)) => (Ok(((self.map.call(ext),), out)), state),
-            Err(err) => (Err(err), state),
+            // All the arguments of the inner parser are mapped into a single one.
+            Err(err) => match err.argument {
+                Some(_) => (Err(err.in_argument(0)), state),
+                None => (Err(err), state),
+            },
         }
     }
     fn regex(&self) -> String {
         self.parser.regex()
     }
+
+    fn suggest_as(&self, source: Source<'_>, input: &str) -> Vec<Suggestion> {
+        self.parser.suggest_as(source, input)
+    }
+
+    fn usage_as(&self, source: Source<'_>) -> String {
+        self.parser.usage_as(source)
+    }
+
+    fn nodes_as(&self, source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        self.parser.nodes_as(source)
+    }
+
+    fn permitted(&self, source: Source<'_>) -> bool {
+        self.parser.permitted(source)
+    }
+
+    fn arguments(&self) -> Vec<&ArgumentInfo> {
+        self.parser.arguments()
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        self.parse_as(Source::NONE, state, input)
+    }
+
+    fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        self.suggest_as(Source::NONE, input)
+    }
+
+    fn usage(&self) -> String {
+        self.usage_as(Source::NONE)
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        self.nodes_as(Source::NONE)
+    }
 }
@@ -1,19 +1,34 @@
 mod and;
+mod error;
 mod evaluator;
+mod flags;
 mod literal;
 mod map;
+mod named;
 mod optional;
+mod or;
+mod repeat;
+mod require;
 mod space;
+mod suggestion;
 
 pub use and::*;
+pub use error::*;
 #[cfg(test)]
 pub(crate) use evaluator::*;
+pub use flags::*;
 pub use literal::*;
 pub use map::*;
+pub use named::*;
 pub use optional::*;
+pub use or::*;
+pub use repeat::*;
+pub use require::*;
 pub use space::*;
+pub use suggestion::*;
 
 use crate::generic::Tuple;
+use crate::graph::{ArgumentParser, NodeKind, StringKind};
 
 pub trait IterParser {
     /// This associated type says what the return value is for the parser. If you have a parser that returns a i32, then set it to Extract = (i32,), or
@@ -29,13 +44,14 @@ pub trait IterParser {
     /// this makes it so we can iterate over many possible attempts at parsing the input.
     /// We have to do this because else its impossible to correctly parse (Option<u32>,  u32) from the input "42".
     /// we first need to try parsing were the option consumes the 42, and then we need to try the case were it does not.
+    /// The range of a returned error is relative to the given input.
     #[allow(clippy::type_complexity)]
     fn parse<'p>(
         &self,
         state: Self::ParserState,
         input: &'p str,
     ) -> (
-        anyhow::Result<(Self::Extract, &'p str)>,
+        Result<(Self::Extract, &'p str), ParseError>,
         Option<Self::ParserState>,
     );
 
@@ -47,6 +63,73 @@ pub trait IterParser {
     /// to be able to parse json then a suitable regex could be "\{.*?\}". Using this regex we can quickly determine what command
     /// a input belongs to.
     fn regex(&self) -> String;
+
+    /// Like [`parse`](IterParser::parse), but checks the requirements on the way against `source`, see
+    /// [`Require`]. Parsers that hold other parsers pass the source on to them, others can use the default
+    /// implementation.
+    #[allow(clippy::type_complexity)]
+    fn parse_as<'p>(
+        &self,
+        _source: Source<'_>,
+        state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        self.parse(state, input)
+    }
+
+    /// Returns the ways the input could be completed, if the input ends inside of, or right after this parser.
+    /// The ranges of the suggestions are relative to the given input. Most parsers only need to look at the
+    /// last word, see [`Suggestion::complete_word`]. Parsers that can't sensibly suggest anything, like numbers,
+    /// can just use the default implementation.
+    fn suggest(&self, _input: &str) -> Vec<Suggestion> {
+        Vec::new()
+    }
+
+    /// Like [`suggest`](IterParser::suggest), but leaves out what `source` is not permitted to use.
+    fn suggest_as(&self, _source: Source<'_>, input: &str) -> Vec<Suggestion> {
+        self.suggest(input)
+    }
+
+    /// Returns how the parser looks in usage text. Literals are shown as is, arguments as `<type>`,
+    /// optionals as `[...]` and choices as `(a|b|c)`. A command made up of parsers could look like
+    /// `/tp <x> <y> <z> [<reason>]`. By default the parser is shown as `<argument>`.
+    fn usage(&self) -> String {
+        String::from("<argument>")
+    }
+
+    /// Like [`usage`](IterParser::usage), but leaves out what `source` is not permitted to use.
+    fn usage_as(&self, _source: Source<'_>) -> String {
+        self.usage()
+    }
+
+    /// Whether `source` is permitted to use at least one way through the parser, see [`Require`]. Parsers that
+    /// hold other parsers combine their answers, others can use the default implementation.
+    fn permitted(&self, _source: Source<'_>) -> bool {
+        true
+    }
+
+    /// Returns the named arguments of the parser, in the order they appear. See [`Named`].
+    fn arguments(&self) -> Vec<&ArgumentInfo> {
+        Vec::new()
+    }
+
+    /// Returns the alternative sequences of Brigadier nodes the parser can be, used to build a
+    /// [`CommandGraph`](crate::graph::CommandGraph). Spaces are implied between nodes, so space parsers return a
+    /// single empty sequence. By default the parser is sent as a single word argument named after its usage.
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        vec![vec![NodeKind::argument(
+            &self.usage(),
+            ArgumentParser::String(StringKind::SingleWord),
+        )]]
+    }
+
+    /// Like [`nodes`](IterParser::nodes), but leaves out what `source` is not permitted to use.
+    fn nodes_as(&self, _source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        self.nodes()
+    }
 }
 
 // This feature cant be implemented before rust gets an upgrade.
@@ -0,0 +1,180 @@
+use super::{And, IterParser, Opt, ParseError, Source, Suggestion};
+use crate::graph::NodeKind;
+
+/// The name and description of an argument, shown in usage and help text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArgumentInfo {
+    pub name: String,
+    pub description: Option<String>,
+
+    /// Values that are suggested when completing the argument.
+    pub examples: Vec<String>,
+}
+
+/// Wraps the parser of an argument, giving it a name. Parses exactly like the parser it wraps, but errors
+/// and suggestions coming from it refer to the argument by name.
+pub struct Named<P> {
+    pub(crate) parser: P,
+    pub(crate) info: ArgumentInfo,
+}
+
+impl<P> Named<P> {
+    pub fn new(parser: P, name: &str) -> Self {
+        Self {
+            parser,
+            info: ArgumentInfo {
+                name: String::from(name),
+                description: None,
+                examples: Vec::new(),
+            },
+        }
+    }
+
+    pub fn describe(mut self, description: &str) -> Self {
+        self.info.description = Some(String::from(description));
+        self
+    }
+
+    pub fn examples<I, S>(mut self, examples: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.info.examples = examples.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn info(&self) -> &ArgumentInfo {
+        &self.info
+    }
+
+    fn tooltip(&self) -> String {
+        match &self.info.description {
+            Some(description) => format!("<{}>: {}", self.info.name, description),
+            None => format!("<{}>", self.info.name),
+        }
+    }
+}
+
+impl<A, P> And<A, Named<P>> {
+    /// Describes the named argument at the end of the chain.
+    pub fn describe(mut self, description: &str) -> Self {
+        self.b = self.b.describe(description);
+        self
+    }
+
+    /// Sets the example values of the named argument at the end of the chain.
+    pub fn examples<I, S>(mut self, examples: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.b = self.b.examples(examples);
+        self
+    }
+}
+
+impl<A, P> And<A, Opt<Named<P>>> {
+    /// Describes the optional named argument at the end of the chain.
+    pub fn describe(mut self, description: &str) -> Self {
+        self.b.parser = self.b.parser.describe(description);
+        self
+    }
+
+    /// Sets the example values of the optional named argument at the end of the chain.
+    pub fn examples<I, S>(mut self, examples: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.b.parser = self.b.parser.examples(examples);
+        self
+    }
+}
+
+impl<P: IterParser> IterParser for Named<P> {
+    type Extract = P::Extract;
+    type ParserState = P::ParserState;
+
+    #[allow(clippy::type_complexity)]
+    fn parse_as<'p>(
+        &self,
+        source: Source<'_>,
+        state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        let (result, state) = self.parser.parse_as(source, state, input);
+        match result {
+            Err(err) if err.argument.is_some() && err.argument_name.is_none() => {
+                (Err(err.named(&self.info.name)), state)
+            }
+            result => (result, state),
+        }
+    }
+
+    fn regex(&self) -> String {
+        self.parser.regex()
+    }
+
+    fn suggest_as(&self, source: Source<'_>, input: &str) -> Vec<Suggestion> {
+        let mut suggestions = self.parser.suggest_as(source, input);
+        for example in &self.info.examples {
+            if let Some(suggestion) = Suggestion::complete_word(input, example) {
+                if !suggestions.contains(&suggestion) {
+                    suggestions.push(suggestion);
+                }
+            }
+        }
+
+        let tooltip = self.tooltip();
+        suggestions
+            .into_iter()
+            .map(|suggestion| suggestion.with_tooltip(&tooltip))
+            .collect()
+    }
+
+    fn usage(&self) -> String {
+        format!("<{}>", self.info.name)
+    }
+
+    fn nodes_as(&self, source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        let mut nodes = self.parser.nodes_as(source);
+        for path in &mut nodes {
+            if let [NodeKind::Argument { name, .. }] = path.as_mut_slice() {
+                *name = self.info.name.clone();
+            }
+        }
+        nodes
+    }
+
+    fn permitted(&self, source: Source<'_>) -> bool {
+        self.parser.permitted(source)
+    }
+
+    fn arguments(&self) -> Vec<&ArgumentInfo> {
+        vec![&self.info]
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        self.parse_as(Source::NONE, state, input)
+    }
+
+    fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        self.suggest_as(Source::NONE, input)
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        self.nodes_as(Source::NONE)
+    }
+}
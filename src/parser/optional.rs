@@ -1,6 +1,7 @@
 use crate::generic::Tuple;
+use crate::graph::NodeKind;
 
-use super::IterParser;
+use super::{ArgumentInfo, IterParser, ParseError, Source, Suggestion};
 
 #[derive(Debug)]
 pub struct Opt<P> {
@@ -28,12 +29,13 @@ where
     type ParserState = OptState<P::ParserState>;
 
     #[allow(clippy::type_complexity)]
-    fn parse<'p>(
+    fn parse_as<'p>(
         &self,
+        source: Source<'_>,
         state: Self::ParserState,
         input: &'p str,
     ) -> (
-        anyhow::Result<(Self::Extract, &'p str)>,
+        Result<(Self::Extract, &'p str), ParseError>,
         Option<Self::ParserState>,
     ) {
         match state {
@@ -44,7 +46,7 @@ where
             }
             OptState::Consume(sub_parser_state) => {
                 // We parse the input trying to consume the start.
-                let (res, sub_parser_state) = self.parser.parse(sub_parser_state, input);
+                let (res, sub_parser_state) = self.parser.parse_as(source, sub_parser_state, input);
 
                 match (res, sub_parser_state) {
                     (Ok((ext, out)), None) => {
@@ -63,6 +65,52 @@ where
     fn regex(&self) -> String {
         format!("({})?", self.parser.regex())
     }
+
+    fn suggest_as(&self, source: Source<'_>, input: &str) -> Vec<Suggestion> {
+        // The case where the optional is skipped is handled by the parser that follows it.
+        self.parser.suggest_as(source, input)
+    }
+
+    fn usage_as(&self, source: Source<'_>) -> String {
+        match self.parser.usage_as(source) {
+            usage if usage.is_empty() => usage,
+            usage => format!("[{}]", usage),
+        }
+    }
+
+    fn nodes_as(&self, source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        let mut nodes = self.parser.nodes_as(source);
+        nodes.push(Vec::new());
+        nodes
+    }
+
+    fn arguments(&self) -> Vec<&ArgumentInfo> {
+        self.parser.arguments()
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        self.parse_as(Source::NONE, state, input)
+    }
+
+    fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        self.suggest_as(Source::NONE, input)
+    }
+
+    fn usage(&self) -> String {
+        self.usage_as(Source::NONE)
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        self.nodes_as(Source::NONE)
+    }
 }
 
 #[cfg(test)]
@@ -74,9 +122,7 @@ mod tests {
 
     #[test]
     fn simple1() {
-        let lit = Literal {
-            value: String::from("tp"),
-        };
+        let lit = Literal::new(String::from("tp"));
 
         let opt = Opt { parser: lit };
 
@@ -92,9 +138,7 @@ mod tests {
 
     #[test]
     fn simple2() {
-        let lit = Literal {
-            value: String::from("tp"),
-        };
+        let lit = Literal::new(String::from("tp"));
 
         let opt = Opt { parser: lit };
 
@@ -110,9 +154,7 @@ mod tests {
 
     #[test]
     fn empty() {
-        let lit = Literal {
-            value: String::from("tp"),
-        };
+        let lit = Literal::new(String::from("tp"));
 
         let opt = Opt { parser: lit };
 
@@ -128,9 +170,7 @@ mod tests {
 
     #[test]
     fn partial() {
-        let lit = Literal {
-            value: String::from("tp"),
-        };
+        let lit = Literal::new(String::from("tp"));
 
         let input = "tpme";
 
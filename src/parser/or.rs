@@ -0,0 +1,176 @@
+use super::{ArgumentInfo, IterParser, ParseError, Source, Suggestion};
+use crate::graph::NodeKind;
+
+/// Tries parser `a`, and when it runs out of attempts, parser `b`. This is how a command forks after a
+/// shared prefix, like `/team (add <name>|remove <name>|list)`.
+#[derive(Debug)]
+pub struct Or<A, B> {
+    pub(crate) a: A,
+    pub(crate) b: B,
+}
+
+/// The extract of an [`Or`], telling which of the alternatives matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Either<A, B> {
+    Left(A),
+    Right(B),
+}
+
+impl<T> Either<T, T> {
+    /// Returns the value, when both alternatives extract the same type.
+    pub fn into_inner(self) -> T {
+        match self {
+            Either::Left(value) | Either::Right(value) => value,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum OrState<A, B> {
+    Left(A),
+    Right(B),
+}
+
+impl<A: Default, B> Default for OrState<A, B> {
+    fn default() -> Self {
+        Self::Left(A::default())
+    }
+}
+
+impl<A: IterParser, B: IterParser> IterParser for Or<A, B> {
+    type Extract = (Either<A::Extract, B::Extract>,);
+    type ParserState = OrState<A::ParserState, B::ParserState>;
+
+    #[allow(clippy::type_complexity)]
+    fn parse_as<'p>(
+        &self,
+        source: Source<'_>,
+        state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        let (result, next_state) = match state {
+            OrState::Left(a_state) => {
+                let (result, a_state) = self.a.parse_as(source, a_state, input);
+                let next_state = match a_state {
+                    Some(a_state) => OrState::Left(a_state),
+                    None => OrState::Right(B::ParserState::default()),
+                };
+                (
+                    result.map(|(ext, out)| (Either::Left(ext), out)),
+                    Some(next_state),
+                )
+            }
+            OrState::Right(b_state) => {
+                let (result, b_state) = self.b.parse_as(source, b_state, input);
+                (
+                    result.map(|(ext, out)| (Either::Right(ext), out)),
+                    b_state.map(OrState::Right),
+                )
+            }
+        };
+
+        match result {
+            Ok((ext, out)) => (Ok(((ext,), out)), next_state),
+            // Whichever alternative failed, its arguments all end up in the single extracted value.
+            Err(err) => match err.argument {
+                Some(_) => (Err(err.in_argument(0)), next_state),
+                None => (Err(err), next_state),
+            },
+        }
+    }
+
+    fn regex(&self) -> String {
+        format!("(({})|({}))", self.a.regex(), self.b.regex())
+    }
+
+    fn suggest_as(&self, source: Source<'_>, input: &str) -> Vec<Suggestion> {
+        let mut suggestions = self.a.suggest_as(source, input);
+        for suggestion in self.b.suggest_as(source, input) {
+            if !suggestions.contains(&suggestion) {
+                suggestions.push(suggestion);
+            }
+        }
+        suggestions
+    }
+
+    /// Alternatives with an empty usage, like those the source is not permitted to use, are left out.
+    fn usage_as(&self, source: Source<'_>) -> String {
+        match (self.a.usage_as(source), self.b.usage_as(source)) {
+            (a, b) if b.is_empty() => a,
+            (a, b) if a.is_empty() => b,
+            (a, b) => format!("({}|{})", a, b),
+        }
+    }
+
+    fn nodes_as(&self, source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        let mut nodes = self.a.nodes_as(source);
+        nodes.extend(self.b.nodes_as(source));
+        nodes
+    }
+
+    fn permitted(&self, source: Source<'_>) -> bool {
+        self.a.permitted(source) || self.b.permitted(source)
+    }
+
+    fn arguments(&self) -> Vec<&ArgumentInfo> {
+        let mut arguments = self.a.arguments();
+        arguments.extend(self.b.arguments());
+        arguments
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        self.parse_as(Source::NONE, state, input)
+    }
+
+    fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        self.suggest_as(Source::NONE, input)
+    }
+
+    fn usage(&self) -> String {
+        self.usage_as(Source::NONE)
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        self.nodes_as(Source::NONE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::evaluator::Evaluator;
+    use crate::parser::literal::Literal;
+
+    use super::*;
+
+    #[test]
+    fn alternatives() {
+        let or = Or {
+            a: Literal::new(String::from("add")),
+            b: Literal::new(String::from("remove")),
+        };
+
+        let eval = Evaluator::new(&or);
+
+        let res = eval.evaluate_all("remove");
+        assert_eq!(res.len(), 2);
+        assert!(res[0].is_err());
+        assert_eq!(res[1].as_ref().unwrap().0, (Either::Right(()),));
+
+        let res = eval.evaluate_all("add");
+        assert_eq!(res[0].as_ref().unwrap().0, (Either::Left(()),));
+        assert!(res[1].is_err());
+
+        assert_eq!(or.usage(), "(add|remove)");
+    }
+}
@@ -0,0 +1,389 @@
+use crate::graph::{ArgumentParser, NodeKind, StringKind};
+
+use super::{
+    ArgumentInfo, IterParser, MaybeSpaces, OneOrMoreSpace, ParseError, ParseErrorKind, Source,
+    Suggestion,
+};
+
+/// What is written between the elements of a [`Repeat`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separator {
+    /// One or more spaces, like `alice bob`.
+    Space,
+    /// A comma with optional spaces around it, like `1,2, 3`. The elements can't contain a comma.
+    Comma,
+}
+
+impl Separator {
+    /// Returns the length of the separator at the start of the input, if there is one.
+    fn skip(self, input: &str) -> Option<usize> {
+        let spaces = |s: &str| s.len() - s.trim_start().len();
+        match self {
+            Separator::Space => Some(spaces(input)).filter(|len| *len > 0),
+            Separator::Comma => {
+                let before = spaces(input);
+                let rest = input[before..].strip_prefix(',')?;
+                Some(before + 1 + spaces(rest))
+            }
+        }
+    }
+
+    /// Cuts the input given to an element parser, so it can't read into the next element.
+    fn element(self, input: &str) -> &str {
+        match self {
+            Separator::Space => input,
+            Separator::Comma => &input[..input.find(',').unwrap_or(input.len())],
+        }
+    }
+
+    fn regex(self) -> String {
+        match self {
+            Separator::Space => OneOrMoreSpace.regex(),
+            Separator::Comma => format!("{},{}", MaybeSpaces {}.regex(), MaybeSpaces {}.regex()),
+        }
+    }
+}
+
+/// Parses the same argument several times, separated by spaces or commas, and extracts them as a `Vec`.
+/// It first takes as many elements as it can, and then gives back one at a time when backtracking, so an
+/// argument following it can still be matched.
+pub struct Repeat<P> {
+    pub(crate) parser: P,
+    pub(crate) min: usize,
+    pub(crate) max: Option<usize>,
+    pub(crate) separator: Separator,
+}
+
+impl<P> Repeat<P> {
+    /// Repeats the parser zero or more times, separated by spaces.
+    pub fn new(parser: P) -> Self {
+        Self {
+            parser,
+            min: 0,
+            max: None,
+            separator: Separator::Space,
+        }
+    }
+
+    pub fn min(mut self, min: usize) -> Self {
+        self.min = min;
+        self
+    }
+
+    pub fn max(mut self, max: usize) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn separator(mut self, separator: Separator) -> Self {
+        self.separator = separator;
+        self
+    }
+}
+
+/// The elements parsed so far, with the position each one ended at.
+pub struct RepeatState<T> {
+    elements: Option<Vec<(T, usize)>>,
+}
+
+impl<T> Default for RepeatState<T> {
+    fn default() -> Self {
+        Self { elements: None }
+    }
+}
+
+impl<P, T> Repeat<P>
+where
+    P: IterParser<Extract = (T,)>,
+{
+    /// Parses a single element with the first alternative of the parser that matches.
+    fn element(&self, source: Source<'_>, input: &str) -> Result<(T, usize), ParseError> {
+        let mut state = Some(P::ParserState::default());
+        let mut error = None;
+        while let Some(current) = state {
+            let (result, next) = self.parser.parse_as(source, current, input);
+            match result {
+                Ok(((value,), out)) => return Ok((value, input.len() - out.len())),
+                Err(err) => error = Some(ParseError::furthest(error, err)),
+            }
+            state = next;
+        }
+        Err(error.expect("the parser gave no result"))
+    }
+
+    /// Parses as many elements as possible. The error is why the next element could not be parsed.
+    fn parse_all(&self, source: Source<'_>, input: &str) -> (Vec<(T, usize)>, Option<ParseError>) {
+        let mut elements = Vec::new();
+        let mut pos = 0;
+        while self.max.map_or(true, |max| elements.len() < max) {
+            let start = if elements.is_empty() {
+                0
+            } else {
+                match self.separator.skip(&input[pos..]) {
+                    Some(len) => pos + len,
+                    None => break,
+                }
+            };
+
+            match self.element(source, self.separator.element(&input[start..])) {
+                // An element that consumes nothing would be repeated forever.
+                Ok((_, 0)) => break,
+                Ok((value, len)) => {
+                    pos = start + len;
+                    elements.push((value, pos));
+                }
+                Err(err) => return (elements, Some(err.shift(start, 0))),
+            }
+        }
+        (elements, None)
+    }
+}
+
+impl<P, T> IterParser for Repeat<P>
+where
+    P: IterParser<Extract = (T,)>,
+    T: Clone,
+{
+    type Extract = (Vec<T>,);
+    type ParserState = RepeatState<T>;
+
+    #[allow(clippy::type_complexity)]
+    fn parse_as<'p>(
+        &self,
+        source: Source<'_>,
+        state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        let mut elements = match state.elements {
+            Some(elements) => elements,
+            None => {
+                let (elements, error) = self.parse_all(source, input);
+                if elements.len() < self.min {
+                    let err = error.unwrap_or_else(|| {
+                        let end = elements.last().map_or(0, |(_, end)| *end);
+                        ParseError::at_token(
+                            ParseErrorKind::TooFewElements(self.min),
+                            &input[end..],
+                        )
+                        .shift(end, 0)
+                        .in_argument(0)
+                    });
+                    return (Err(err), None);
+                }
+                elements
+            }
+        };
+
+        let end = elements.last().map_or(0, |(_, end)| *end);
+        let values = elements.iter().map(|(value, _)| value.clone()).collect();
+
+        // The next alternative gives back the last element.
+        let next = if elements.len() > self.min {
+            elements.pop();
+            Some(RepeatState {
+                elements: Some(elements),
+            })
+        } else {
+            None
+        };
+        (Ok(((values,), &input[end..])), next)
+    }
+
+    fn regex(&self) -> String {
+        // The counts are checked while parsing, so the regex only tells if there can be no elements.
+        let element = self.parser.regex();
+        let repeated = format!(
+            "({e})(({s})({e}))*",
+            e = element,
+            s = self.separator.regex()
+        );
+        if self.min == 0 {
+            format!("({})?", repeated)
+        } else {
+            repeated
+        }
+    }
+
+    /// Suggests completions for the element at the end of the input.
+    fn suggest_as(&self, source: Source<'_>, input: &str) -> Vec<Suggestion> {
+        let mut start = 0;
+        let mut count = 1;
+        loop {
+            let rest = &input[start..];
+            match self.element(source, self.separator.element(rest)) {
+                Ok((_, len)) if len > 0 && self.max.map_or(true, |max| count < max) => {
+                    match self.separator.skip(&rest[len..]) {
+                        Some(separator) => {
+                            start += len + separator;
+                            count += 1;
+                        }
+                        None => break,
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        self.parser
+            .suggest_as(source, &input[start..])
+            .into_iter()
+            .map(|suggestion| suggestion.shift(start))
+            .collect()
+    }
+
+    fn usage_as(&self, source: Source<'_>) -> String {
+        let separator = match self.separator {
+            Separator::Space => "",
+            Separator::Comma => ",",
+        };
+        let usage = format!("{}{}...", self.parser.usage_as(source), separator);
+        if self.min == 0 {
+            format!("[{}]", usage)
+        } else {
+            usage
+        }
+    }
+
+    /// Brigadier can't repeat an argument, so the elements are exported as a greedy string.
+    fn nodes_as(&self, source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        let mut nodes = vec![vec![NodeKind::argument(
+            &self.parser.usage_as(source),
+            ArgumentParser::String(StringKind::GreedyPhrase),
+        )]];
+        if self.min == 0 {
+            nodes.push(Vec::new());
+        }
+        nodes
+    }
+
+    fn permitted(&self, source: Source<'_>) -> bool {
+        self.min == 0 || self.parser.permitted(source)
+    }
+
+    fn arguments(&self) -> Vec<&ArgumentInfo> {
+        self.parser.arguments()
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        self.parse_as(Source::NONE, state, input)
+    }
+
+    fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        self.suggest_as(Source::NONE, input)
+    }
+
+    fn usage(&self) -> String {
+        self.usage_as(Source::NONE)
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        self.nodes_as(Source::NONE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::builder::{literal, CommandBuilder};
+    use crate::command::{Command, CommandSpec};
+
+    #[test]
+    fn many() {
+        let command: CommandSpec<(), Vec<String>, _, _, _> = literal("/kill")
+            .space()
+            .many1::<String>()
+            .on_call(|players: Vec<String>| move || players.clone());
+
+        assert_eq!(
+            command.call((), "/kill alice bob  carol").unwrap(),
+            vec!["alice", "bob", "carol"]
+        );
+        assert_eq!(command.call((), "/kill alice").unwrap(), vec!["alice"]);
+        assert!(command.call((), "/kill").is_err());
+        assert_eq!(command.usage(), "/kill <string>...");
+
+        let command: CommandSpec<(), usize, _, _, _> = literal("/count")
+            .opt_space()
+            .many::<u32>()
+            .on_call(|numbers: Vec<u32>| move || numbers.len());
+
+        assert_eq!(command.call((), "/count").unwrap(), 0);
+        assert_eq!(command.call((), "/count 1 2").unwrap(), 2);
+        let err = command.call((), "/count 1 x").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::TooManyArguments);
+        assert_eq!(command.usage(), "/count [<u32>...]");
+    }
+
+    #[test]
+    fn backtracking() {
+        let command: CommandSpec<(), (Vec<String>, u32), _, _, _> = literal("/give")
+            .space()
+            .many1::<String>()
+            .space()
+            .arg::<u32>()
+            .on_call(|players: Vec<String>, count: u32| move || (players.clone(), count));
+
+        assert_eq!(
+            command.call((), "/give alice bob 5").unwrap(),
+            (vec![String::from("alice"), String::from("bob")], 5)
+        );
+        assert!(command.call((), "/give 5").is_err());
+    }
+
+    #[test]
+    fn counts_and_separators() {
+        let command: CommandSpec<(), Vec<u32>, _, _, _> = literal("/sum")
+            .space()
+            .followed_by(
+                Repeat::new(crate::argument::U32Parser::default())
+                    .min(2)
+                    .max(3)
+                    .separator(Separator::Comma),
+            )
+            .on_call(|numbers: Vec<u32>| move || numbers.clone());
+
+        assert_eq!(command.call((), "/sum 1,2, 3").unwrap(), vec![1, 2, 3]);
+        assert_eq!(command.call((), "/sum 1 , 2").unwrap(), vec![1, 2]);
+        assert_eq!(command.usage(), "/sum <u32>,...");
+
+        let err = command.call((), "/sum 1").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::TooFewElements(2));
+        assert_eq!(err.range, 6..6);
+
+        let err = command.call((), "/sum 1,x").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidNumber);
+        assert_eq!(err.range, 7..8);
+
+        let err = command.call((), "/sum 1,2,3,4").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::TooManyArguments);
+        assert_eq!(err.range, 10..12);
+    }
+
+    #[test]
+    fn suggest() {
+        let command: CommandSpec<(), usize, _, _, _> = literal("/toggle")
+            .space()
+            .many1::<bool>()
+            .on_call(|values: Vec<bool>| move || values.len());
+
+        assert_eq!(
+            command.suggest("/toggle true f", 14),
+            vec![Suggestion::new(13..14, String::from("false"))]
+        );
+        assert_eq!(
+            command.suggest("/toggle t", 9),
+            vec![Suggestion::new(8..9, String::from("true"))]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use std::any::Any;
+
+use crate::graph::NodeKind;
+
+use super::{ArgumentInfo, IterParser, ParseError, ParseErrorKind, Suggestion};
+
+type Predicate = Box<dyn Fn(Source<'_>) -> bool>;
+
+/// Who or what uses a command, like the player sending it, that the requirements of [`Require`] are checked
+/// against. It is passed to the `_as` methods of parsers and commands, like
+/// [`Command::call_as`](crate::command::Command::call_as).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Source<'a>(Option<&'a dyn Any>);
+
+impl<'a> Source<'a> {
+    /// No source. This is what the methods without `_as` use, so no requirement is met through them.
+    pub const NONE: Source<'static> = Source(None);
+
+    pub fn new<S: 'static>(source: &'a S) -> Self {
+        Self(Some(source))
+    }
+
+    /// Returns the source as the type a requirement expects, or None if there is no source or it is of
+    /// another type.
+    fn get<S: 'static>(self) -> Option<&'a S> {
+        self.0?.downcast_ref()
+    }
+}
+
+/// A parser that only lets the source use it, and everything after it, when a predicate holds, see [`Source`].
+/// Otherwise it fails with [`ParseErrorKind::PermissionDenied`], and is left out of suggestions, usage and the
+/// command graph.
+///
+/// The predicate takes a source rather than the game state of the command. The game state is only handed to the
+/// handler after parsing, and suggestions, usage and the graph are made without one, so it can't be checked while
+/// parsing. Parsers also don't know which command, and so which game state or source type, they end up in, which
+/// is why the source is checked at runtime: a predicate that takes another type than the given source is never
+/// met.
+pub struct Require<P> {
+    pub(crate) parser: P,
+    pub(crate) predicate: Predicate,
+}
+
+impl<P> Require<P> {
+    pub fn new<S: 'static>(parser: P, predicate: impl Fn(&S) -> bool + 'static) -> Self {
+        Self {
+            parser,
+            // Without a source of the type the predicate takes, there is no one to permit.
+            predicate: Box::new(move |source| source.get().is_some_and(&predicate)),
+        }
+    }
+}
+
+impl<P: IterParser> IterParser for Require<P> {
+    type Extract = P::Extract;
+    type ParserState = P::ParserState;
+
+    #[allow(clippy::type_complexity)]
+    fn parse<'p>(
+        &self,
+        state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        self.parse_as(Source::NONE, state, input)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn parse_as<'p>(
+        &self,
+        source: Source<'_>,
+        state: Self::ParserState,
+        input: &'p str,
+    ) -> (
+        Result<(Self::Extract, &'p str), ParseError>,
+        Option<Self::ParserState>,
+    ) {
+        if !(self.predicate)(source) {
+            return (
+                Err(ParseError::at_token(
+                    ParseErrorKind::PermissionDenied,
+                    input,
+                )),
+                None,
+            );
+        }
+        self.parser.parse_as(source, state, input)
+    }
+
+    fn regex(&self) -> String {
+        self.parser.regex()
+    }
+
+    fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        self.suggest_as(Source::NONE, input)
+    }
+
+    fn suggest_as(&self, source: Source<'_>, input: &str) -> Vec<Suggestion> {
+        if !(self.predicate)(source) {
+            return Vec::new();
+        }
+        self.parser.suggest_as(source, input)
+    }
+
+    fn usage(&self) -> String {
+        self.usage_as(Source::NONE)
+    }
+
+    /// Parsers the source is not permitted to use have an empty usage.
+    fn usage_as(&self, source: Source<'_>) -> String {
+        if !(self.predicate)(source) {
+            return String::new();
+        }
+        self.parser.usage_as(source)
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        self.nodes_as(Source::NONE)
+    }
+
+    fn nodes_as(&self, source: Source<'_>) -> Vec<Vec<NodeKind>> {
+        if !(self.predicate)(source) {
+            return Vec::new();
+        }
+        self.parser.nodes_as(source)
+    }
+
+    fn permitted(&self, source: Source<'_>) -> bool {
+        (self.predicate)(source) && self.parser.permitted(source)
+    }
+
+    fn arguments(&self) -> Vec<&ArgumentInfo> {
+        self.parser.arguments()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::builder::{literal, CommandBuilder};
+    use crate::command::dispatcher::Dispatcher;
+    use crate::command::{Command, CommandId};
+    use crate::graph::CommandGraph;
+    use crate::parser::Opt;
+
+    struct Player {
+        op: bool,
+    }
+
+    const OP: Player = Player { op: true };
+    const GUEST: Player = Player { op: false };
+
+    fn dispatcher() -> Dispatcher<(), &'static str> {
+        let mut dispatcher = Dispatcher::new();
+        dispatcher
+            .register(
+                literal("/stop")
+                    .requires(|player: &Player| player.op)
+                    .on_call(|| move || "stopped"),
+            )
+            .unwrap();
+        dispatcher
+            .register(
+                literal("/team")
+                    .space()
+                    .branch(
+                        literal("add").requires(|player: &Player| player.op),
+                        literal("list"),
+                    )
+                    .on_call(|_| move || "team"),
+            )
+            .unwrap();
+        dispatcher.compile();
+        dispatcher
+    }
+
+    #[test]
+    fn parse() {
+        let dispatcher = dispatcher();
+        let op = Source::new(&OP);
+        let guest = Source::new(&GUEST);
+
+        assert_eq!(dispatcher.call_as(op, (), "/stop"), Ok("stopped"));
+        assert_eq!(dispatcher.call_as(op, (), "/team add"), Ok("team"));
+        assert_eq!(dispatcher.call_as(guest, (), "/team list"), Ok("team"));
+
+        let err = dispatcher.call_as(guest, (), "/stop").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::PermissionDenied);
+        assert_eq!(err.range, 0..5);
+
+        let err = dispatcher.call_as(guest, (), "/team add").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::PermissionDenied);
+        assert_eq!(err.range, 6..9);
+    }
+
+    #[test]
+    fn missing_or_wrong_source() {
+        let dispatcher = dispatcher();
+
+        for &source in &[Source::NONE, Source::new(&5)] {
+            let err = dispatcher.call_as(source, (), "/stop").unwrap_err();
+            assert_eq!(err.kind, ParseErrorKind::PermissionDenied);
+
+            let suggestions: Vec<_> = dispatcher
+                .suggest_as(source, "/", 1)
+                .into_iter()
+                .map(|suggestion| suggestion.text)
+                .collect();
+            assert_eq!(suggestions, vec!["/team"]);
+            assert_eq!(dispatcher.usage_as(source), "/team list");
+
+            let graph = CommandGraph::from_command_as(&dispatcher, source);
+            assert!(graph.child(0, "stop").is_none());
+        }
+
+        // The methods without a source deny every requirement.
+        let err = dispatcher.call((), "/stop").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::PermissionDenied);
+        assert_eq!(dispatcher.usage(), "/team list");
+        assert!(CommandGraph::from_command(&dispatcher)
+            .child(0, "stop")
+            .is_none());
+    }
+
+    #[test]
+    fn permitted() {
+        let op = Source::new(&OP);
+        let guest = Source::new(&GUEST);
+
+        let kick = literal("/kick")
+            .space()
+            .followed_by(literal("all").requires(|player: &Player| player.op));
+        assert!(kick.permitted(op));
+        assert!(!kick.permitted(guest));
+
+        // A requirement that can be skipped leaves the rest permitted.
+        let optional = Opt {
+            parser: literal("all").requires(|player: &Player| player.op),
+        };
+        assert!(optional.permitted(guest));
+
+        let dispatcher = dispatcher();
+        assert!(!dispatcher.get(CommandId::of(0)).unwrap().permitted(guest));
+        assert!(dispatcher.get(CommandId::of(1)).unwrap().permitted(guest));
+    }
+
+    #[test]
+    fn hidden() {
+        let dispatcher = dispatcher();
+        let op = Source::new(&OP);
+        let guest = Source::new(&GUEST);
+
+        let suggestions = |source: Source<'_>, input: &str| {
+            dispatcher
+                .suggest_as(source, input, input.len())
+                .into_iter()
+                .map(|suggestion| suggestion.text)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(suggestions(op, "/"), vec!["/stop", "/team"]);
+        assert_eq!(suggestions(guest, "/"), vec!["/team"]);
+        assert_eq!(suggestions(guest, "/team "), vec!["list"]);
+
+        assert_eq!(dispatcher.usage_as(op), "/stop\n/team (add|list)");
+        assert_eq!(dispatcher.usage_as(guest), "/team list");
+
+        let graph = CommandGraph::from_command_as(&dispatcher, guest);
+        assert!(graph.child(0, "stop").is_none());
+        let team = graph.child(0, "team").unwrap();
+        assert!(graph.child(team, "add").is_none());
+        assert!(graph.child(team, "list").is_some());
+    }
+}
@@ -1,5 +1,5 @@
-use super::IterParser;
-use anyhow::anyhow;
+use super::{IterParser, ParseError, ParseErrorKind};
+use crate::graph::NodeKind;
 
 #[derive(Debug)]
 pub enum OnceState {
@@ -50,13 +50,16 @@ impl IterParser for OneOrMoreSpace {
         _state: Self::ParserState,
         input: &'p str,
     ) -> (
-        anyhow::Result<(Self::Extract, &'p str)>,
+        Result<(Self::Extract, &'p str), ParseError>,
         Option<Self::ParserState>,
     ) {
         let before_len = input.len();
         let out = input.trim_start();
         if out.len() == before_len {
-            (Err(anyhow!("Expected a space at input '{}'", input)), None)
+            (
+                Err(ParseError::at_token(ParseErrorKind::ExpectedSpace, input)),
+                None,
+            )
         } else {
             (Ok(((), out)), None)
         }
@@ -65,6 +68,14 @@ impl IterParser for OneOrMoreSpace {
     fn regex(&self) -> String {
         "\\s+".to_string()
     }
+
+    fn usage(&self) -> String {
+        String::from(" ")
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        vec![Vec::new()]
+    }
 }
 
 impl IterParser for MaybeSpaces {
@@ -77,7 +88,7 @@ impl IterParser for MaybeSpaces {
         _state: Self::ParserState,
         input: &'p str,
     ) -> (
-        anyhow::Result<(Self::Extract, &'p str)>,
+        Result<(Self::Extract, &'p str), ParseError>,
         Option<Self::ParserState>,
     ) {
         let out = input.trim_start();
@@ -88,6 +99,14 @@ impl IterParser for MaybeSpaces {
     fn regex(&self) -> String {
         "\\s*".to_string()
     }
+
+    fn usage(&self) -> String {
+        String::from(" ")
+    }
+
+    fn nodes(&self) -> Vec<Vec<NodeKind>> {
+        vec![Vec::new()]
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,54 @@
+use std::ops::Range;
+
+/// A possible completion of the input. The range is the bytes of the input the text should replace.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Suggestion {
+    pub range: Range<usize>,
+    pub text: String,
+
+    /// Extra information shown next to the suggestion, like what argument it is for.
+    pub tooltip: Option<String>,
+}
+
+impl Suggestion {
+    pub fn new(range: Range<usize>, text: String) -> Self {
+        Self {
+            range,
+            text,
+            tooltip: None,
+        }
+    }
+
+    /// Sets the tooltip, unless the suggestion already has one.
+    pub fn with_tooltip(mut self, tooltip: &str) -> Self {
+        if self.tooltip.is_none() {
+            self.tooltip = Some(String::from(tooltip));
+        }
+        self
+    }
+
+    /// Suggests replacing the last word of the input with the text, if the word is a prefix of it.
+    /// Leading whitespace is skipped, and nothing is suggested if there are more words after the first.
+    pub fn complete_word(input: &str, text: &str) -> Option<Self> {
+        let word = input.trim_start();
+        if word.contains(char::is_whitespace) {
+            return None;
+        }
+
+        let start = input.len() - word.len();
+        let word_lower = word.to_lowercase();
+        if text.to_lowercase().starts_with(&word_lower) {
+            Some(Self::new(start..input.len(), text.to_string()))
+        } else {
+            None
+        }
+    }
+
+    /// Moves the range of the suggestion, used when the input given to a parser was a suffix of the full input.
+    pub(crate) fn shift(self, offset: usize) -> Self {
+        Self {
+            range: self.range.start + offset..self.range.end + offset,
+            ..self
+        }
+    }
+}
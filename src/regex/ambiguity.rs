@@ -0,0 +1,105 @@
+use super::{CmdPos, StateId, DFA};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    hash::Hash,
+};
+
+/// The bytes in the order they are tried when building an example, so examples are readable when they can be.
+fn preferred_bytes() -> impl Iterator<Item = u8> {
+    (b' '..=b'~').chain(0..b' ').chain(0x7f..=0xff)
+}
+
+impl<C: Copy + Hash + Eq + Ord> DFA<CmdPos<C>> {
+    /// Finds every pair of commands that both match some input, with the shortest such input. A state of the
+    /// dfa that is the end of several commands is reached by inputs that all of them match.
+    ///
+    /// This must be called on the dfa of all the commands, not the early termination dfa, since that one stops
+    /// as soon as only a single command is left.
+    pub fn overlapping_ends(&self) -> BTreeMap<(C, C), Vec<u8>> {
+        let mut overlaps = BTreeMap::new();
+        if self.states.is_empty() {
+            return overlaps;
+        }
+
+        // A breadth first search, so the first path to a state is one of the shortest.
+        let mut paths: Vec<Option<Vec<u8>>> = vec![None; self.states.len()];
+        let mut queue = VecDeque::new();
+        paths[0] = Some(Vec::new());
+        queue.push_back(StateId::of(0));
+
+        while let Some(id) = queue.pop_front() {
+            let path = paths[id.0 as usize].clone().unwrap_or_default();
+
+            let mut ends: Vec<C> = self[id]
+                .associations
+                .iter()
+                .filter_map(|assoc| match assoc {
+                    CmdPos::End(c) => Some(*c),
+                    CmdPos::Mid(_) => None,
+                })
+                .collect();
+            ends.sort();
+            ends.dedup();
+            for (i, a) in ends.iter().enumerate() {
+                for b in &ends[i + 1..] {
+                    overlaps.entry((*a, *b)).or_insert_with(|| path.clone());
+                }
+            }
+
+            for b in preferred_bytes() {
+                if let Some(next) = self[(id, b)] {
+                    let next_path = &mut paths[next.0 as usize];
+                    if next_path.is_none() {
+                        let mut path = path.clone();
+                        path.push(b);
+                        *next_path = Some(path);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        overlaps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::regex::{CmdPos, DFA, NFA};
+
+    fn dfa(regexes: &[&str]) -> DFA<CmdPos<usize>> {
+        let mut nfa = NFA::<CmdPos<usize>>::empty();
+        for (id, regex) in regexes.iter().enumerate() {
+            let command = NFA::from_command_regex(regex, id).unwrap();
+            nfa = if id == 0 {
+                command
+            } else {
+                nfa.or(command).unwrap()
+            };
+        }
+        nfa.into()
+    }
+
+    #[test]
+    fn overlaps() {
+        let overlaps =
+            dfa(&["/tp [0-9]+", "/tp [a-z0-9]+", "/kill", "/tp [0-9]+ [0-9]+"]).overlapping_ends();
+
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[&(0, 1)], b"/tp 0");
+    }
+
+    #[test]
+    fn no_overlaps() {
+        assert!(dfa(&["/tp [0-9]+", "/tp [a-z]+", "/kill"])
+            .overlapping_ends()
+            .is_empty());
+        assert!(DFA::<CmdPos<usize>>::new().overlapping_ends().is_empty());
+    }
+
+    #[test]
+    fn readable_examples() {
+        let overlaps = dfa(&["/say\\s.*", "/say [^x]+"]).overlapping_ends();
+        assert_eq!(overlaps[&(0, 1)], b"/say  ");
+    }
+}
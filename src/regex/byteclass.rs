@@ -23,6 +23,16 @@ impl ByteClass {
     pub(crate) fn set(&mut self, index: u8, value: u8) {
         self.0[index as usize] = value;
     }
+
+    pub(crate) fn as_bytes(&self) -> &[u8; 256] {
+        &self.0
+    }
+}
+
+impl From<[u8; 256]> for ByteClass {
+    fn from(values: [u8; 256]) -> Self {
+        Self(values)
+    }
 }
 
 impl From<u8> for ByteClass {
@@ -13,15 +13,15 @@ pub struct DfaState<A> {
     // A list of maximum length 256, but usually much shorter.
     // It lists all the states self is connected to. None means
     // a none existent state.
-    table: Vec<Option<StateId>>,
+    pub(crate) table: Vec<Option<StateId>>,
 
     // A byteclass is a [u8; 256], and says how to move from
     // one state to another. If lets say dfa[self.class][c] == 5, then in
     // terms of a dfa pictorial representation, we have a edge going from
     // self to self.table[c], with the value 5 associated with that edge.
-    class: ByteClassId,
+    pub(crate) class: ByteClassId,
 
-    associations: HashSet<A>,
+    pub(crate) associations: HashSet<A>,
 }
 
 impl<A> DfaState<A> {
@@ -45,7 +45,7 @@ impl<A: Eq + std::hash::Hash> DfaState<A> {
 pub struct DFA<A> {
     /// The states are the nodes of the DFA.
     pub(crate) states: Vec<DfaState<A>>,
-    ends: Vec<StateId>,
+    pub(crate) ends: Vec<StateId>,
     pub(crate) transitions: IndexSet<ByteClass>,
 }
 
@@ -23,7 +23,7 @@ use std::{
 // 4 a) Do a dfs to find all states reachable from the start. Remove the nodes that we cant reach.
 // 4 b) Remove all unused byteclasses (Edges).
 
-#[derive(Debug, Hash, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
 pub enum CmdPos<C: Copy + std::hash::Hash + PartialEq + Eq> {
     End(C),
     Mid(C),
@@ -204,6 +204,20 @@ impl<C: Copy + std::hash::Hash + Eq + std::fmt::Debug> DFA<CmdPos<C>> {
             },
         }
     }
+
+    /// Returns every command that the input could be the start of, whether or not one of them already ends
+    /// there. Unlike [`DFA::early_termination_find`], a command that ends at the input doesn't hide the longer
+    /// commands that continue it, which is what completing the input needs.
+    pub fn early_termination_prefix_find(&self, input: &str) -> Vec<C> {
+        match self.find(input) {
+            Ok(id) | Err(Some(id)) => self
+                .associations(id)
+                .into_iter()
+                .map(|cp| *cp.value())
+                .collect(),
+            Err(None) => Vec::new(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,227 @@
+// https://en.wikipedia.org/wiki/DFA_minimization#Hopcroft's_algorithm
+
+use super::{dfa::DFA, stateid::StateId};
+use std::collections::{HashMap, HashSet};
+
+/// The number of states of a dfa before and after [`DFA::minimize`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Minimized {
+    pub states_before: usize,
+    pub states_after: usize,
+}
+
+impl<A: std::hash::Hash + Ord + Clone> DFA<A> {
+    /// Merges the states that can't be told apart, using Hopcroft's algorithm. Two states are only merged if
+    /// both or neither are ends, they have the same associations, and every input leads them to states that are
+    /// merged too. The inputs that are matched, and the associations of the state any input ends in, stay the
+    /// same.
+    pub fn minimize(&mut self) -> Minimized {
+        let states_before = self.states.len();
+        if states_before == 0 {
+            return Minimized {
+                states_before,
+                states_after: 0,
+            };
+        }
+
+        // A missing transition goes to a dead state after the real ones. It keeps a block of its own, so a real
+        // state is never replaced by a missing transition.
+        let dead = states_before;
+        let target = |state: usize, b: u8| match state == dead {
+            true => dead,
+            false => self[(StateId::of(state), b)].map_or(dead, |id| id.0 as usize),
+        };
+
+        // Bytes that lead every state to the same place are the same to the algorithm.
+        let mut classes: Vec<Vec<u8>> = Vec::new();
+        let mut columns: HashMap<Vec<usize>, usize> = HashMap::new();
+        for b in 0..=255_u8 {
+            let column: Vec<usize> = (0..dead).map(|state| target(state, b)).collect();
+            let class = *columns.entry(column).or_insert_with(|| {
+                classes.push(Vec::new());
+                classes.len() - 1
+            });
+            classes[class].push(b);
+        }
+
+        // inverse[class][state] lists the states that go to `state` on a byte of `class`.
+        let mut inverse = vec![vec![Vec::new(); dead + 1]; classes.len()];
+        for (class, bytes) in classes.iter().enumerate() {
+            for state in 0..=dead {
+                inverse[class][target(state, bytes[0])].push(state);
+            }
+        }
+
+        // The initial blocks group the states that are ends alike and have the same associations. Most states
+        // of a dfa for many commands have associations of their own, so the blocks are looked up by key.
+        let ends: HashSet<StateId> = self.ends.iter().copied().collect();
+        let mut blocks: Vec<Vec<usize>> = Vec::new();
+        let mut keys: HashMap<(bool, Vec<&A>), usize> = HashMap::new();
+        let mut block_of: Vec<usize> = (0..dead)
+            .map(|state| {
+                let id = StateId::of(state);
+                let mut associations: Vec<&A> = self[id].associations.iter().collect();
+                associations.sort();
+                let block = *keys
+                    .entry((ends.contains(&id), associations))
+                    .or_insert_with(|| {
+                        blocks.push(Vec::new());
+                        blocks.len() - 1
+                    });
+                blocks[block].push(state);
+                block
+            })
+            .collect();
+        blocks.push(vec![dead]);
+        block_of.push(blocks.len() - 1);
+
+        // The pairs of a block and a class that still have to be used to split other blocks. A block that is
+        // split keeps its pairs, so only the new half needs to be added.
+        let mut waiting: Vec<(usize, usize)> = (0..blocks.len())
+            .flat_map(|block| (0..classes.len()).map(move |class| (block, class)))
+            .collect();
+
+        let mut marked = vec![false; dead + 1];
+        while let Some((splitter, class)) = waiting.pop() {
+            // The states that go into the splitter on this class, grouped by their block.
+            let mut touched: Vec<usize> = Vec::new();
+            for &target in &blocks[splitter] {
+                for &source in &inverse[class][target] {
+                    if !marked[source] {
+                        marked[source] = true;
+                        let block = block_of[source];
+                        if !touched.contains(&block) {
+                            touched.push(block);
+                        }
+                    }
+                }
+            }
+
+            for block in touched {
+                let (inside, outside): (Vec<usize>, Vec<usize>) =
+                    blocks[block].iter().partition(|&&state| marked[state]);
+                for &state in &inside {
+                    marked[state] = false;
+                }
+                if outside.is_empty() {
+                    continue;
+                }
+
+                // The new block gets the smaller half, so it is cheap to use as a splitter.
+                let (stay, split) = if inside.len() <= outside.len() {
+                    (outside, inside)
+                } else {
+                    (inside, outside)
+                };
+                let new_block = blocks.len();
+                for &state in &split {
+                    block_of[state] = new_block;
+                }
+                blocks[block] = stay;
+                blocks.push(split);
+                waiting.extend((0..classes.len()).map(|class| (new_block, class)));
+            }
+        }
+
+        // Build the new dfa, keeping the block of the start state first.
+        let mut new_ids: Vec<Option<StateId>> = vec![None; blocks.len()];
+        let mut dfa = DFA::new();
+        let mut order = vec![block_of[0]];
+        order.extend(
+            (0..blocks.len()).filter(|&block| block != block_of[0] && block != block_of[dead]),
+        );
+        for &block in &order {
+            new_ids[block] = Some(dfa.push_state());
+        }
+
+        for &block in &order {
+            let representative = blocks[block][0];
+            let id = StateId::of(representative);
+            let new_id = new_ids[block].expect("every block but the dead one has an id");
+            let transitions = (0..=255_u8).map(|b| new_ids[block_of[target(representative, b)]]);
+            dfa.set_transitions(new_id, transitions);
+            dfa.associate(new_id, self[id].associations.clone());
+            if ends.contains(&id) {
+                dfa.push_end(new_id);
+            }
+        }
+
+        *self = dfa;
+        Minimized {
+            states_before,
+            states_after: self.states.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regex::{CmdPos, NFA};
+
+    #[test]
+    fn merges_equivalent_states() {
+        // Both branches end in the same way, so the states after `a` and `b` can be merged.
+        let mut dfa: DFA<usize> = NFA::<usize>::regex("(a|b)cd").unwrap().into();
+        let before = dfa.number_of_states();
+        let minimized = dfa.minimize();
+
+        assert_eq!(minimized.states_before, before);
+        assert_eq!(minimized.states_after, 4);
+        assert!(dfa.find("acd").is_ok());
+        assert!(dfa.find("bcd").is_ok());
+        assert!(dfa.find("cd").is_err());
+        assert!(dfa.find("acdd").is_err());
+    }
+
+    #[test]
+    fn keeps_associations_apart() {
+        let nfa = NFA::<CmdPos<usize>>::from_command_regex("/a x", 0)
+            .unwrap()
+            .or(NFA::from_command_regex("/b x", 1).unwrap())
+            .unwrap();
+        let mut dfa: DFA<CmdPos<usize>> = nfa.into();
+        let before = dfa.number_of_states();
+        let minimized = dfa.minimize();
+
+        // The states after `/a` and `/b` have the same future, but belong to different commands.
+        assert_eq!(minimized.states_after, before);
+        assert_eq!(dfa.early_termination_find("/a x"), Ok(vec![0]));
+        assert_eq!(dfa.early_termination_find("/b x"), Ok(vec![1]));
+    }
+
+    #[quickcheck]
+    fn same_language(inputs: Vec<String>) -> bool {
+        let regex = r"/(tp|teleport) +([0-9]+|~)( +[0-9]+)?|/kill( [a-z]+)*|/say [a-z ]*";
+        let dfa: DFA<usize> = NFA::<usize>::regex(regex).unwrap().into();
+        let mut minimized = dfa.clone();
+        minimized.minimize();
+
+        let examples = [
+            "/tp 1",
+            "/teleport ~ 2",
+            "/kill",
+            "/kill a b",
+            "/say hi",
+            "/tp",
+            "/kil",
+        ];
+        examples
+            .iter()
+            .map(|example| example.to_string())
+            .chain(inputs)
+            .all(|input| dfa.find(&input).is_ok() == minimized.find(&input).is_ok())
+    }
+
+    #[test]
+    fn empty() {
+        let mut dfa = DFA::<usize>::new();
+        assert_eq!(
+            dfa.minimize(),
+            Minimized {
+                states_before: 0,
+                states_after: 0
+            }
+        );
+    }
+}
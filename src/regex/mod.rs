@@ -1,15 +1,20 @@
+mod ambiguity;
 mod byteclass;
 pub mod dfa;
 pub mod early_termination;
+mod minimize;
 pub mod nfa;
 mod nfa_to_dfa;
 mod qc;
 mod regex_to_nfa;
+mod serialize;
 pub mod stateid;
 mod utf8_range_to_nfa;
 
 pub use dfa::*;
 pub use early_termination::*;
+pub use minimize::Minimized;
 pub use nfa::*;
 pub use regex_to_nfa::we_support_regex;
+pub use serialize::{checksum, Association, LoadError};
 pub use stateid::*;
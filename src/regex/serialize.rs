@@ -0,0 +1,350 @@
+// The format, with every number in little endian:
+//
+//   magic       b"LTDF"
+//   version     u16
+//   checksum    u64, see `checksum`
+//   classes     u32 count, then 256 bytes per byte class
+//   states      u32 count, then per state:
+//                 class         u16
+//                 table         u16 count, then u32 per entry, u32::MAX for no state
+//                 associations  u32 count, then the bytes of each association, sorted
+//   ends        u32 count, then u32 per end
+
+use super::{
+    byteclass::{ByteClass, ByteClassId},
+    CmdPos, StateId, DFA,
+};
+use std::{collections::HashSet, convert::TryInto, error::Error, fmt, hash::Hash};
+
+const MAGIC: &[u8; 4] = b"LTDF";
+const VERSION: u16 = 1;
+const NO_STATE: u32 = u32::MAX;
+
+/// A value associated with the states of a dfa that can be written with the dfa by [`DFA::to_bytes`].
+pub trait Association: Sized {
+    fn write(&self, out: &mut Vec<u8>);
+
+    /// Reads the value from the start of `bytes`, and advances past it. Returns `None` if the bytes don't hold a
+    /// value.
+    fn read(bytes: &mut &[u8]) -> Option<Self>;
+}
+
+impl Association for usize {
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&(*self as u64).to_le_bytes());
+    }
+
+    fn read(bytes: &mut &[u8]) -> Option<Self> {
+        read_u64(bytes)?.try_into().ok()
+    }
+}
+
+impl<C: Association + Copy + Hash + Eq> Association for CmdPos<C> {
+    fn write(&self, out: &mut Vec<u8>) {
+        let (tag, c) = match self {
+            CmdPos::End(c) => (0, c),
+            CmdPos::Mid(c) => (1, c),
+        };
+        out.push(tag);
+        c.write(out);
+    }
+
+    fn read(bytes: &mut &[u8]) -> Option<Self> {
+        match take(bytes, 1)?[0] {
+            0 => Some(CmdPos::End(C::read(bytes)?)),
+            1 => Some(CmdPos::Mid(C::read(bytes)?)),
+            _ => None,
+        }
+    }
+}
+
+/// Why [`DFA::from_bytes`] rejected its input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    /// The bytes are not a serialized dfa.
+    BadMagic,
+    /// The dfa was written by a version of the format this one can't read.
+    UnsupportedVersion(u16),
+    /// The dfa was built from other regexes than the ones it is loaded for, so it must be built again.
+    ChecksumMismatch { expected: u64, found: u64 },
+    /// The bytes are truncated, or don't describe a valid dfa.
+    Corrupt,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::BadMagic => write!(f, "not a serialized dfa"),
+            LoadError::UnsupportedVersion(version) => {
+                write!(f, "unsupported dfa format version {}", version)
+            }
+            LoadError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "the dfa was built from other regexes, expected checksum {:016x} but found {:016x}",
+                expected, found
+            ),
+            LoadError::Corrupt => write!(f, "the serialized dfa is corrupt"),
+        }
+    }
+}
+
+impl Error for LoadError {}
+
+/// A checksum of the regexes a dfa is built from, in order. It is stable across runs and platforms, so it can be
+/// stored with the dfa to notice when the regexes have changed.
+pub fn checksum<I, S>(regexes: I) -> u64
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    // FNV-1a, with a byte that can't occur in a str between the regexes.
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut add = |b: u8| {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    };
+    for regex in regexes {
+        regex.as_ref().bytes().for_each(&mut add);
+        add(0xff);
+    }
+    hash
+}
+
+fn take<'a>(bytes: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+    if bytes.len() < n {
+        return None;
+    }
+    let (head, tail) = bytes.split_at(n);
+    *bytes = tail;
+    Some(head)
+}
+
+fn read_u16(bytes: &mut &[u8]) -> Option<u16> {
+    Some(u16::from_le_bytes(take(bytes, 2)?.try_into().ok()?))
+}
+
+fn read_u32(bytes: &mut &[u8]) -> Option<u32> {
+    Some(u32::from_le_bytes(take(bytes, 4)?.try_into().ok()?))
+}
+
+fn read_u64(bytes: &mut &[u8]) -> Option<u64> {
+    Some(u64::from_le_bytes(take(bytes, 8)?.try_into().ok()?))
+}
+
+impl<A: Association + Hash + Eq + Clone> DFA<A> {
+    /// Writes the dfa in a compact binary format, together with a `checksum` of what it was built from, usually
+    /// made with [`checksum`]. The same dfa always gives the same bytes.
+    pub fn to_bytes(&self, checksum: u64) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.transitions.len() * 256 + self.states.len() * 16);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.extend_from_slice(&checksum.to_le_bytes());
+
+        out.extend_from_slice(&(self.transitions.len() as u32).to_le_bytes());
+        for class in &self.transitions {
+            out.extend_from_slice(class.as_bytes());
+        }
+
+        out.extend_from_slice(&(self.states.len() as u32).to_le_bytes());
+        for state in &self.states {
+            out.extend_from_slice(&state.class.0.to_le_bytes());
+            out.extend_from_slice(&(state.table.len() as u16).to_le_bytes());
+            for target in &state.table {
+                let target = target.map_or(NO_STATE, |id| id.0);
+                out.extend_from_slice(&target.to_le_bytes());
+            }
+
+            // The order of a hash set changes between runs, so the associations are sorted by their bytes.
+            let mut associations: Vec<Vec<u8>> = state
+                .associations
+                .iter()
+                .map(|association| {
+                    let mut bytes = Vec::new();
+                    association.write(&mut bytes);
+                    bytes
+                })
+                .collect();
+            associations.sort();
+            out.extend_from_slice(&(associations.len() as u32).to_le_bytes());
+            associations.iter().for_each(|bytes| out.extend(bytes));
+        }
+
+        out.extend_from_slice(&(self.ends.len() as u32).to_le_bytes());
+        for end in &self.ends {
+            out.extend_from_slice(&end.0.to_le_bytes());
+        }
+        out
+    }
+
+    /// Reads a dfa written by [`DFA::to_bytes`]. Fails if the bytes were written with another checksum than
+    /// `checksum`, or don't hold a valid dfa. The byte classes, which are most of the dfa, are copied as they are.
+    pub fn from_bytes(bytes: &[u8], checksum: u64) -> Result<Self, LoadError> {
+        let mut bytes = bytes;
+        let bytes = &mut bytes;
+
+        if take(bytes, MAGIC.len()) != Some(&MAGIC[..]) {
+            return Err(LoadError::BadMagic);
+        }
+        let version = read_u16(bytes).ok_or(LoadError::Corrupt)?;
+        if version != VERSION {
+            return Err(LoadError::UnsupportedVersion(version));
+        }
+        let found = read_u64(bytes).ok_or(LoadError::Corrupt)?;
+        if found != checksum {
+            return Err(LoadError::ChecksumMismatch {
+                expected: checksum,
+                found,
+            });
+        }
+
+        Self::read_body(bytes).ok_or(LoadError::Corrupt)
+    }
+
+    fn read_body(bytes: &mut &[u8]) -> Option<Self> {
+        let mut dfa = DFA::new();
+
+        let classes = read_u32(bytes)? as usize;
+        for _ in 0..classes {
+            let class: [u8; 256] = take(bytes, 256)?.try_into().ok()?;
+            let (_, inserted) = dfa.transitions.insert_full(ByteClass::from(class));
+            if !inserted {
+                return None;
+            }
+        }
+
+        let states = read_u32(bytes)? as usize;
+        // Every state takes at least 8 bytes, so a bad count can't make us allocate much.
+        if bytes.len() < states.saturating_mul(8) {
+            return None;
+        }
+        dfa.states.reserve(states);
+        for _ in 0..states {
+            let id = dfa.push_state();
+            let class = ByteClassId(read_u16(bytes)?);
+            let table_len = read_u16(bytes)? as usize;
+            let table = (0..table_len)
+                .map(|_| match read_u32(bytes)? {
+                    NO_STATE => Some(None),
+                    target if (target as usize) < states => Some(Some(StateId(target))),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            // Every byte of the class must lead to an entry of the table.
+            let max = dfa
+                .transitions
+                .get_index(class.0 as usize)?
+                .as_bytes()
+                .iter()
+                .max();
+            if max.map_or(true, |&max| max as usize >= table.len()) {
+                return None;
+            }
+
+            let associations = read_u32(bytes)?;
+            let associations = (0..associations)
+                .map(|_| A::read(bytes))
+                .collect::<Option<HashSet<A>>>()?;
+
+            let state = &mut dfa.states[id.0 as usize];
+            state.class = class;
+            state.table = table;
+            state.associations = associations;
+        }
+
+        let ends = read_u32(bytes)?;
+        for _ in 0..ends {
+            let end = read_u32(bytes)?;
+            if end as usize >= states {
+                return None;
+            }
+            dfa.push_end(StateId(end));
+        }
+
+        match bytes.is_empty() {
+            true => Some(dfa),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regex::NFA;
+
+    fn dfa() -> DFA<CmdPos<usize>> {
+        NFA::<CmdPos<usize>>::from_command_regex("/tp [0-9]+", 0)
+            .unwrap()
+            .or(NFA::from_command_regex("/kill( [a-z]+)?", 1).unwrap())
+            .unwrap()
+            .into_early_termination_dfa()
+    }
+
+    #[test]
+    fn round_trip() {
+        let dfa = dfa();
+        let bytes = dfa.to_bytes(7);
+        let loaded = DFA::<CmdPos<usize>>::from_bytes(&bytes, 7).unwrap();
+
+        assert_eq!(loaded.number_of_states(), dfa.number_of_states());
+        assert_eq!(loaded.rough_size_bytes(), dfa.rough_size_bytes());
+        // The candidates come from a hash set, so their order differs between the two.
+        let find = |dfa: &DFA<CmdPos<usize>>, input: &str| {
+            let mut candidates = dfa.early_termination_find(input);
+            match &mut candidates {
+                Ok(ids) | Err(ids) => ids.sort(),
+            }
+            candidates
+        };
+        for input in ["/tp 12", "/tp", "/kill", "/kill me", "/kil", "/say hi", ""].iter() {
+            assert_eq!(find(&loaded, input), find(&dfa, input));
+        }
+        assert_eq!(loaded.to_bytes(7), bytes);
+    }
+
+    #[test]
+    fn rejects() {
+        let bytes = dfa().to_bytes(7);
+        let load = |bytes: &[u8], checksum| DFA::<CmdPos<usize>>::from_bytes(bytes, checksum);
+
+        assert_eq!(
+            load(&bytes, 8).unwrap_err(),
+            LoadError::ChecksumMismatch {
+                expected: 8,
+                found: 7
+            }
+        );
+        assert_eq!(load(b"hello", 7).unwrap_err(), LoadError::BadMagic);
+
+        let mut newer = bytes.clone();
+        newer[4] = 2;
+        assert_eq!(
+            load(&newer, 7).unwrap_err(),
+            LoadError::UnsupportedVersion(2)
+        );
+
+        for len in [14, 20, bytes.len() - 1].iter() {
+            assert_eq!(load(&bytes[..*len], 7).unwrap_err(), LoadError::Corrupt);
+        }
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert_eq!(load(&longer, 7).unwrap_err(), LoadError::Corrupt);
+    }
+
+    #[test]
+    fn empty() {
+        let dfa = DFA::<usize>::new();
+        let loaded = DFA::<usize>::from_bytes(&dfa.to_bytes(0), 0).unwrap();
+        assert_eq!(loaded.number_of_states(), 0);
+        assert_eq!(loaded.find("a"), Err(None));
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(checksum(["/tp", "/kill"]), checksum(vec!["/tp", "/kill"]));
+        assert_ne!(checksum(["/tp", "/kill"]), checksum(["/kill", "/tp"]));
+        assert_ne!(checksum(["/tp", "/kill"]), checksum(["/tp/kill"]));
+        assert_ne!(checksum(["/tp"]), checksum(Vec::<&str>::new()));
+    }
+}